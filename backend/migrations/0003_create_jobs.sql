@@ -0,0 +1,49 @@
+-- The synced job catalog. Supersedes the old unversioned jobs_table_migration.sql,
+-- whose table had no source, expiry or de-duplication columns; a table left by it
+-- is upgraded in place so its rows are kept.
+CREATE TABLE IF NOT EXISTS jobs (
+    id TEXT PRIMARY KEY,
+    source TEXT,
+    external_id TEXT,
+    title TEXT NOT NULL,
+    company TEXT NOT NULL,
+    company_logo TEXT,
+    location TEXT,
+    location_info JSONB,
+    url TEXT NOT NULL,
+    description TEXT NOT NULL,
+    job_type TEXT,
+    salary TEXT,
+    salary_range JSONB,
+    category TEXT,
+    created_at TIMESTAMPTZ NOT NULL,
+    expired_at TIMESTAMPTZ,
+    duplicate_of TEXT,
+    source_urls JSONB NOT NULL DEFAULT '[]',
+    -- Order in the catalog; de-duplication keeps the earliest entry canonical
+    position BIGINT NOT NULL DEFAULT 0
+);
+
+-- The old table: UUID ids generated by the database, and one row per URL
+ALTER TABLE jobs ALTER COLUMN id DROP DEFAULT;
+ALTER TABLE jobs ALTER COLUMN id TYPE TEXT USING id::text;
+ALTER TABLE jobs DROP CONSTRAINT IF EXISTS jobs_url_key;
+UPDATE jobs SET created_at = NOW() WHERE created_at IS NULL;
+ALTER TABLE jobs ALTER COLUMN created_at DROP DEFAULT;
+ALTER TABLE jobs ALTER COLUMN created_at SET NOT NULL;
+ALTER TABLE jobs ADD COLUMN IF NOT EXISTS source TEXT;
+ALTER TABLE jobs ADD COLUMN IF NOT EXISTS company_logo TEXT;
+ALTER TABLE jobs ADD COLUMN IF NOT EXISTS location_info JSONB;
+ALTER TABLE jobs ADD COLUMN IF NOT EXISTS salary_range JSONB;
+ALTER TABLE jobs ADD COLUMN IF NOT EXISTS expired_at TIMESTAMPTZ;
+ALTER TABLE jobs ADD COLUMN IF NOT EXISTS duplicate_of TEXT;
+ALTER TABLE jobs ADD COLUMN IF NOT EXISTS source_urls JSONB NOT NULL DEFAULT '[]';
+ALTER TABLE jobs ADD COLUMN IF NOT EXISTS position BIGINT NOT NULL DEFAULT 0;
+
+DROP INDEX IF EXISTS idx_jobs_url;
+DROP INDEX IF EXISTS idx_jobs_external_id;
+CREATE INDEX IF NOT EXISTS idx_jobs_source_external_id ON jobs(source, external_id);
+CREATE INDEX IF NOT EXISTS idx_jobs_title ON jobs(title);
+CREATE INDEX IF NOT EXISTS idx_jobs_company ON jobs(company);
+CREATE INDEX IF NOT EXISTS idx_jobs_created_at ON jobs(created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_jobs_search ON jobs USING gin(to_tsvector('english', title || ' ' || company));
@@ -0,0 +1,16 @@
+-- Resume uploads, for a profile (application_id NULL) or pinned to an application.
+-- The files themselves stay in the uploads directory.
+CREATE TABLE IF NOT EXISTS resumes (
+    id TEXT PRIMARY KEY,
+    user_id TEXT NOT NULL,
+    application_id TEXT,
+    version INTEGER,
+    filename TEXT NOT NULL,
+    original_filename TEXT NOT NULL,
+    content_type TEXT NOT NULL,
+    size_bytes BIGINT NOT NULL,
+    uploaded_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_resumes_user_id ON resumes(user_id, uploaded_at DESC);
+CREATE INDEX IF NOT EXISTS idx_resumes_application_id ON resumes(application_id);
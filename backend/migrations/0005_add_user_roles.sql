@@ -0,0 +1,2 @@
+-- Authorization role: 'user' or 'admin'
+ALTER TABLE users ADD COLUMN IF NOT EXISTS role TEXT NOT NULL DEFAULT 'user';
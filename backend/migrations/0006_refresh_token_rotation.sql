@@ -0,0 +1,12 @@
+-- Rotation: every sign-in starts a family of refresh tokens. Rotated and logged-out
+-- tokens stay, revoked, until they expire so that a replayed one can be recognised.
+ALTER TABLE refresh_tokens ADD COLUMN IF NOT EXISTS family_id TEXT;
+ALTER TABLE refresh_tokens ADD COLUMN IF NOT EXISTS expires_at TIMESTAMPTZ;
+ALTER TABLE refresh_tokens ADD COLUMN IF NOT EXISTS revoked_at TIMESTAMPTZ;
+ALTER TABLE refresh_tokens ADD COLUMN IF NOT EXISTS replaced_by TEXT;
+
+UPDATE refresh_tokens SET family_id = token_hash WHERE family_id IS NULL;
+UPDATE refresh_tokens SET expires_at = created_at + INTERVAL '30 days' WHERE expires_at IS NULL;
+ALTER TABLE refresh_tokens ALTER COLUMN family_id SET NOT NULL, ALTER COLUMN expires_at SET NOT NULL;
+
+CREATE INDEX IF NOT EXISTS idx_refresh_tokens_family_id ON refresh_tokens(family_id);
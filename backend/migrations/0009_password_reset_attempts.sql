@@ -0,0 +1,2 @@
+-- Wrong codes entered against a reset code; it stops working after a few
+ALTER TABLE password_resets ADD COLUMN IF NOT EXISTS attempts INTEGER NOT NULL DEFAULT 0;
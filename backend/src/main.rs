@@ -4,6 +4,8 @@ mod models;
 mod state;
 mod utils;
 mod routes;
+mod sources;
+mod storage;
 
 use actix_cors::Cors;
 use actix_web::{web, App, HttpServer, middleware::Logger};
@@ -19,7 +21,13 @@ async fn main() -> std::io::Result<()> {
     let port: u16 = env::var("PORT").ok().and_then(|p| p.parse().ok()).unwrap_or(8080);
     let jwt_secret = env::var("JWT_SECRET").unwrap_or_else(|_| "dev-secret-change-me".to_string());
 
-    let app_state = state::init_state(jwt_secret);
+    let storage = storage::Storage::from_env().await.map_err(std::io::Error::other)?;
+    let app_state = state::init_state(jwt_secret, storage).await.map_err(std::io::Error::other)?;
+
+    // Keep the job catalog fresh in the background
+    actix_web::rt::spawn(routes::ingestion::run_worker(app_state.clone(), routes::ingestion::IngestionConfig::from_env()));
+    // Drop refresh tokens once they expire
+    actix_web::rt::spawn(utils::sessions::run_sweeper(app_state.clone()));
 
     HttpServer::new(move || {
         let cors = Cors::permissive();
@@ -34,8 +42,15 @@ async fn main() -> std::io::Result<()> {
                     .configure(routes::health::config)
                     .configure(routes::auth::config)
                     .configure(routes::users::config)
+                    .configure(routes::ingestion::config)
                     .configure(routes::jobs::config)
                     .configure(routes::resume::config)
+                    .configure(routes::applications::config)
+                    .configure(routes::feedback::config)
+                    .configure(routes::analytics::config)
+                    .configure(routes::ml::config)
+                    .configure(routes::notifications::config)
+                    .configure(routes::alerts::config)
             )
     })
     .bind((host.as_str(), port))?
@@ -23,6 +23,36 @@ pub struct User {
     pub updated_at: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub resume_filename: Option<String>,
+    #[serde(default)]
+    pub role: Role,
+    // Google account (`sub` claim) linked for Google sign-in
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub google_sub: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    #[default]
+    User,
+    Admin,
+}
+
+impl Role {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Role::User => "user",
+            Role::Admin => "admin",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Role> {
+        match s.trim().to_lowercase().as_str() {
+            "user" => Some(Role::User),
+            "admin" => Some(Role::Admin),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -34,7 +64,36 @@ pub struct AuthResponse {
     pub refresh_expires_at: String,
 }
 
+/// A password reset code sent by email. Only the code's hash is kept; it works once,
+/// until `expires_at` or until too many wrong codes were entered for the account.
 #[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PasswordResetRecord {
+    pub token_hash: String,
+    pub user_id: String,
+    pub created_at: String,
+    pub expires_at: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub used_at: Option<String>,
+    #[serde(default)]
+    pub attempts: u32,
+}
+
+/// An issued refresh token, stored under its hash. Each sign-in starts a family; every
+/// refresh revokes the token it used and issues the next one in the same family.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RefreshTokenRecord {
+    pub user_id: String,
+    pub family_id: String,
+    pub issued_at: String,
+    pub expires_at: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub revoked_at: Option<String>,
+    // Hash of the token issued in exchange for this one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub replaced_by: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct Job {
     pub id: String,
     pub title: String,
@@ -43,17 +102,129 @@ pub struct Job {
     pub company_logo: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub location: Option<String>,
+    // Normalized from `location` at ingestion
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub location_info: Option<JobLocation>,
     pub url: String,
     pub description: String,
     pub created_at: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub external_id: Option<String>,
+    // Name of the job source that produced it; entries cached before sources existed are Remotive
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub job_type: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub salary: Option<String>,
+    // Parsed from `salary` at ingestion; None when the text has no usable amount
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub salary_range: Option<SalaryRange>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub category: Option<String>,
+    // Set by sync when the posting disappears upstream
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expired_at: Option<String>,
+    // Set by de-duplication on every copy of a posting except the canonical one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duplicate_of: Option<String>,
+    // On the canonical copy: every source listing this posting, itself included
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub source_urls: Vec<SourceUrl>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SalaryPeriod {
+    Hour,
+    Day,
+    Week,
+    Month,
+    Year,
+}
+
+impl SalaryPeriod {
+    /// Multiplier to a yearly amount, assuming full-time work (40h weeks, 52 weeks).
+    pub fn per_year(&self) -> f64 {
+        match self {
+            SalaryPeriod::Hour => 2080.0,
+            SalaryPeriod::Day => 260.0,
+            SalaryPeriod::Week => 52.0,
+            SalaryPeriod::Month => 12.0,
+            SalaryPeriod::Year => 1.0,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct SalaryRange {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max: Option<f64>,
+    // ISO 4217 code when the text names a currency or a recognizable symbol
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub currency: Option<String>,
+    pub period: SalaryPeriod,
+}
+
+impl SalaryRange {
+    pub fn annual_min(&self) -> Option<f64> {
+        self.min.or(self.max).map(|v| v * self.period.per_year())
+    }
+
+    pub fn annual_max(&self) -> Option<f64> {
+        self.max.or(self.min).map(|v| v * self.period.per_year())
+    }
+
+    /// Whether the annualized range overlaps `min..=max`; an open bound always matches.
+    pub fn overlaps(&self, min: Option<f64>, max: Option<f64>) -> bool {
+        if let Some(min) = min {
+            if self.annual_max().map(|v| v < min).unwrap_or(true) { return false; }
+        }
+        if let Some(max) = max {
+            if self.annual_min().map(|v| v > max).unwrap_or(true) { return false; }
+        }
+        true
+    }
+}
+
+/// Who may apply, as far as the location text tells: open worldwide, limited to
+/// regions (e.g. `europe`, `north_america`) or ISO country codes, and/or to a band
+/// of UTC offsets in hours.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+pub struct JobLocation {
+    pub worldwide: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub regions: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub countries: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timezone: Option<TimezoneBand>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub struct TimezoneBand {
+    pub min_offset: f64,
+    pub max_offset: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct SourceUrl {
+    pub job_id: String,
+    pub source: String,
+    pub url: String,
+}
+
+impl Job {
+    pub fn source_name(&self) -> &str {
+        self.source.as_deref().unwrap_or("remotive")
+    }
+
+    /// Active and not a duplicate of another catalog entry.
+    pub fn is_listed(&self) -> bool {
+        self.expired_at.is_none() && self.duplicate_of.is_none()
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -64,6 +235,72 @@ pub struct JobsResponse {
     pub offset: usize,
 }
 
+/// A `/jobs/search` result: the job plus its relevance score and the query words
+/// wrapped in `<mark>` in the title and a description excerpt.
+#[derive(Debug, Serialize)]
+pub struct SearchHit {
+    #[serde(flatten)]
+    pub job: Job,
+    pub score: f64,
+    pub highlights: SearchHighlights,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SearchHighlights {
+    pub title: String,
+    pub snippet: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SearchResponse {
+    pub jobs: Vec<SearchHit>,
+    pub total: usize,
+    pub limit: usize,
+    pub offset: usize,
+    pub facets: SearchFacets,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FacetCount {
+    pub value: String,
+    pub count: usize,
+}
+
+/// A yearly pay band; `min`/`max` are the `salary_min`/`salary_max` values selecting it.
+#[derive(Debug, Serialize)]
+pub struct SalaryBucket {
+    pub value: String,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    pub count: usize,
+}
+
+/// Result counts per filter value for the current search. Each facet ignores its
+/// own filter, so the counts say how many results choosing that value would give.
+#[derive(Debug, Serialize)]
+pub struct SearchFacets {
+    pub category: Vec<FacetCount>,
+    pub job_type: Vec<FacetCount>,
+    pub company: Vec<FacetCount>,
+    pub region: Vec<FacetCount>,
+    pub salary: Vec<SalaryBucket>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct SyncReport {
+    pub sources: Vec<String>,
+    // "<source>: <error>" for every source that could not be fetched this run
+    pub failed_sources: Vec<String>,
+    pub synced_count: usize,
+    pub jobs_added: usize,
+    pub jobs_updated: usize,
+    pub jobs_expired: usize,
+    // Active jobs hidden as copies of a posting listed by another entry
+    pub duplicates: usize,
+    pub total_active: usize,
+    pub synced_at: String,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct JobRecommendation {
     pub job: Job,
@@ -71,3 +308,291 @@ pub struct JobRecommendation {
     pub skills_matched: i32,
     pub missing_skills: Vec<String>,
 }
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum ApplicationStatus {
+    Pending,
+    Viewed,
+    UnderReview,
+    Interviewing,
+    Accepted,
+    Rejected,
+    Withdrawn,
+}
+
+impl ApplicationStatus {
+    pub const ALL: [ApplicationStatus; 7] = [
+        ApplicationStatus::Pending,
+        ApplicationStatus::Viewed,
+        ApplicationStatus::UnderReview,
+        ApplicationStatus::Interviewing,
+        ApplicationStatus::Accepted,
+        ApplicationStatus::Rejected,
+        ApplicationStatus::Withdrawn,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ApplicationStatus::Pending => "pending",
+            ApplicationStatus::Viewed => "viewed",
+            ApplicationStatus::UnderReview => "under_review",
+            ApplicationStatus::Interviewing => "interviewing",
+            ApplicationStatus::Accepted => "accepted",
+            ApplicationStatus::Rejected => "rejected",
+            ApplicationStatus::Withdrawn => "withdrawn",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<ApplicationStatus> {
+        let s = s.trim().to_lowercase();
+        ApplicationStatus::ALL.iter().copied().find(|st| st.as_str() == s)
+    }
+
+    pub fn is_terminal(&self) -> bool {
+        matches!(self, ApplicationStatus::Accepted | ApplicationStatus::Rejected | ApplicationStatus::Withdrawn)
+    }
+
+    // Position in the pending -> interviewing pipeline; terminal states have none.
+    fn stage(&self) -> Option<u8> {
+        match self {
+            ApplicationStatus::Pending => Some(0),
+            ApplicationStatus::Viewed => Some(1),
+            ApplicationStatus::UnderReview => Some(2),
+            ApplicationStatus::Interviewing => Some(3),
+            _ => None,
+        }
+    }
+
+    /// Applications only move forward through the pipeline (stages may be skipped,
+    /// since the applicant rarely sees every step). Rejection and withdrawal are
+    /// possible from any open state; an offer is only accepted after interviewing.
+    pub fn can_transition_to(&self, next: ApplicationStatus) -> bool {
+        if self.is_terminal() || *self == next { return false; }
+        match next {
+            ApplicationStatus::Rejected | ApplicationStatus::Withdrawn => true,
+            ApplicationStatus::Accepted => *self == ApplicationStatus::Interviewing,
+            _ => match (self.stage(), next.stage()) {
+                (Some(cur), Some(nxt)) => nxt > cur,
+                _ => false,
+            },
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ApplicationStatusChange {
+    pub status: ApplicationStatus,
+    pub changed_at: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ResumeVersion {
+    pub version: u32,
+    pub filename: String,
+    pub original_filename: String,
+    pub content_type: String,
+    pub size_bytes: u64,
+    pub uploaded_at: String,
+}
+
+/// One uploaded resume file, for a profile or as a version pinned to an application.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ResumeRecord {
+    pub id: String,
+    pub user_id: String,
+    pub application_id: Option<String>,
+    pub version: Option<u32>,
+    pub filename: String,
+    pub original_filename: String,
+    pub content_type: String,
+    pub size_bytes: u64,
+    pub uploaded_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Application {
+    pub id: String,
+    pub user_id: String,
+    pub job_id: String,
+    pub status: ApplicationStatus,
+    pub applied_at: String,
+    pub updated_at: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub match_score: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub job_title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub company_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub job_category: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cover_letter: Option<String>,
+    #[serde(default)]
+    pub status_history: Vec<ApplicationStatusChange>,
+    // Pinned resume: the file actually sent with this application
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resume_filename: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resume_uploaded_at: Option<String>,
+    #[serde(default)]
+    pub resume_versions: Vec<ResumeVersion>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CategoryOutcomes {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+    pub sample_size: usize,
+    pub successes: usize,
+    pub success_rate: f64,
+    pub base_rate: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ApplicationInsights {
+    pub application_id: String,
+    pub success_probability: f64,
+    pub improvement_suggestions: Vec<String>,
+    pub similar_successful_profiles: Vec<String>,
+    pub match_score: f64,
+    pub matching_skills: Vec<String>,
+    pub missing_skills: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub required_experience_years: Option<i32>,
+    pub category_outcomes: CategoryOutcomes,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ResponseRate {
+    pub name: String,
+    pub applications: usize,
+    pub responses: usize,
+    pub response_rate: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ApplicationStatistics {
+    pub total: usize,
+    pub pending: usize,
+    // Applications that reached interviewing at any point
+    pub interviewed: usize,
+    pub rejected: usize,
+    pub accepted: usize,
+    pub by_status: std::collections::HashMap<String, usize>,
+    pub response_rate: f64,
+    pub by_category: Vec<ResponseRate>,
+    pub by_company: Vec<ResponseRate>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub median_response_hours: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum FeedbackType {
+    JobRecommendation,
+    MatchAccuracy,
+    ApplicationOutcome,
+    SkillRelevance,
+}
+
+impl FeedbackType {
+    pub const ALL: [FeedbackType; 4] = [
+        FeedbackType::JobRecommendation,
+        FeedbackType::MatchAccuracy,
+        FeedbackType::ApplicationOutcome,
+        FeedbackType::SkillRelevance,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FeedbackType::JobRecommendation => "job_recommendation",
+            FeedbackType::MatchAccuracy => "match_accuracy",
+            FeedbackType::ApplicationOutcome => "application_outcome",
+            FeedbackType::SkillRelevance => "skill_relevance",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<FeedbackType> {
+        let s = s.trim().to_lowercase();
+        FeedbackType::ALL.iter().copied().find(|t| t.as_str() == s)
+    }
+
+    /// Inclusive rating range: star ratings for recommendations, match accuracy and
+    /// skill relevance; 0 (unsuccessful) or 1 (successful) for application outcomes.
+    pub fn rating_range(&self) -> (i32, i32) {
+        match self {
+            FeedbackType::ApplicationOutcome => (0, 1),
+            _ => (1, 5),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct UserFeedback {
+    pub id: String,
+    pub user_id: String,
+    pub job_id: String,
+    pub feedback_type: FeedbackType,
+    pub rating: i32,
+    pub timestamp: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ScoreAdjustment {
+    pub factor: String,
+    // Relative change applied to the base match score, e.g. 0.1 = +10%
+    pub delta: f64,
+    pub reason: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationKind {
+    ApplicationStatus,
+    JobMatch,
+    JobAlert,
+    ResumeProcessed,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Notification {
+    pub id: String,
+    pub user_id: String,
+    pub kind: NotificationKind,
+    pub title: String,
+    pub description: String,
+    pub timestamp: String,
+    pub read: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct JobAlert {
+    pub id: String,
+    pub user_id: String,
+    pub name: String,
+    #[serde(default)]
+    pub keywords: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub company: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_match_score: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>,
+    pub enabled: bool,
+    pub created_at: String,
+    pub updated_at: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_triggered_at: Option<String>,
+    // Ids of listed jobs already announced for this alert, so re-syncs don't repeat them
+    #[serde(default, alias = "notified_external_ids")]
+    pub notified_job_ids: Vec<String>,
+}
@@ -0,0 +1,204 @@
+use actix_web::{web, HttpResponse};
+use chrono::Utc;
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+use crate::models::{ApiResponse, Job, JobAlert, NotificationKind};
+use crate::routes::notifications::push_notification;
+use crate::state::{AppState, save_job_alerts};
+use crate::utils::auth::{forbidden, AuthUser};
+use crate::utils::location::{eligibility, job_location, resolve_place, Eligibility};
+use crate::utils::matching::compute_weighted_match;
+use crate::utils::text::strip_html;
+
+#[derive(Deserialize)]
+pub struct AlertPayload {
+    pub name: Option<String>,
+    pub keywords: Option<Vec<String>>,
+    pub category: Option<String>,
+    pub company: Option<String>,
+    pub min_match_score: Option<f64>,
+    pub location: Option<String>,
+    pub enabled: Option<bool>,
+}
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg
+        .route("/alerts/users/{user_id}", web::post().to(create_alert))
+        .route("/alerts/users/{user_id}", web::get().to(list_alerts))
+        .route("/alerts/{id}", web::put().to(update_alert))
+        .route("/alerts/{id}", web::delete().to(delete_alert));
+}
+
+fn non_empty(v: &Option<String>) -> Option<String> {
+    v.as_ref().map(|s| s.trim().to_string()).filter(|s| !s.is_empty())
+}
+
+fn validate_alert(alert: &JobAlert) -> Result<(), String> {
+    if alert.keywords.is_empty() && alert.category.is_none() && alert.company.is_none() && alert.location.is_none() && alert.min_match_score.is_none() {
+        return Err("Alert needs at least one of keywords, category, company, location or min_match_score".into());
+    }
+    if let Some(s) = alert.min_match_score {
+        if !(0.0..=100.0).contains(&s) { return Err("min_match_score must be between 0 and 100".into()); }
+    }
+    Ok(())
+}
+
+fn apply_payload(alert: &mut JobAlert, payload: &AlertPayload) {
+    if let Some(n) = non_empty(&payload.name) { alert.name = n; }
+    if let Some(k) = &payload.keywords {
+        alert.keywords = k.iter().map(|s| s.trim().to_string()).filter(|s| !s.is_empty()).collect();
+    }
+    if payload.category.is_some() { alert.category = non_empty(&payload.category); }
+    if payload.company.is_some() { alert.company = non_empty(&payload.company); }
+    if payload.location.is_some() { alert.location = non_empty(&payload.location); }
+    if payload.min_match_score.is_some() { alert.min_match_score = payload.min_match_score; }
+    if let Some(e) = payload.enabled { alert.enabled = e; }
+}
+
+async fn create_alert(state: web::Data<AppState>, auth: AuthUser, path: web::Path<String>, payload: web::Json<AlertPayload>) -> HttpResponse {
+    let user_id = path.into_inner();
+    if !auth.can_access(&user_id) { return forbidden(); }
+    if !state.users.lock().unwrap().iter().any(|u| u.id == user_id) {
+        return HttpResponse::NotFound().json(ApiResponse::<serde_json::Value> { success: false, data: None, message: "User not found".into() });
+    }
+    let now = Utc::now().to_rfc3339();
+    let mut alert = JobAlert {
+        id: Uuid::new_v4().to_string(),
+        user_id,
+        name: "Job alert".into(),
+        keywords: vec![],
+        category: None,
+        company: None,
+        min_match_score: None,
+        location: None,
+        enabled: true,
+        created_at: now.clone(),
+        updated_at: now,
+        last_triggered_at: None,
+        notified_job_ids: vec![],
+    };
+    apply_payload(&mut alert, &payload);
+    if let Err(msg) = validate_alert(&alert) {
+        return HttpResponse::BadRequest().json(ApiResponse::<serde_json::Value> { success: false, data: None, message: msg });
+    }
+
+    let mut alerts = state.job_alerts.lock().unwrap();
+    alerts.push(alert.clone());
+    let _ = save_job_alerts(&alerts);
+    HttpResponse::Ok().json(ApiResponse { success: true, data: Some(alert), message: "Job alert created".into() })
+}
+
+async fn list_alerts(state: web::Data<AppState>, auth: AuthUser, path: web::Path<String>) -> HttpResponse {
+    let user_id = path.into_inner();
+    if !auth.can_access(&user_id) { return forbidden(); }
+    let alerts = state.job_alerts.lock().unwrap();
+    let results: Vec<JobAlert> = alerts.iter().filter(|a| a.user_id == user_id).cloned().collect();
+    HttpResponse::Ok().json(ApiResponse { success: true, data: Some(results), message: "Job alerts fetched".into() })
+}
+
+async fn update_alert(state: web::Data<AppState>, auth: AuthUser, path: web::Path<String>, payload: web::Json<AlertPayload>) -> HttpResponse {
+    let id = path.into_inner();
+    let mut alerts = state.job_alerts.lock().unwrap();
+    let alert = match alerts.iter_mut().find(|a| a.id == id) {
+        Some(a) if !auth.can_access(&a.user_id) => return forbidden(),
+        Some(a) => a,
+        None => return HttpResponse::NotFound().json(ApiResponse::<serde_json::Value> { success: false, data: None, message: "Job alert not found".into() }),
+    };
+    let mut updated = alert.clone();
+    apply_payload(&mut updated, &payload);
+    if let Err(msg) = validate_alert(&updated) {
+        return HttpResponse::BadRequest().json(ApiResponse::<serde_json::Value> { success: false, data: None, message: msg });
+    }
+    updated.updated_at = Utc::now().to_rfc3339();
+    *alert = updated.clone();
+    let _ = save_job_alerts(&alerts);
+    HttpResponse::Ok().json(ApiResponse { success: true, data: Some(updated), message: "Job alert updated".into() })
+}
+
+async fn delete_alert(state: web::Data<AppState>, auth: AuthUser, path: web::Path<String>) -> HttpResponse {
+    let id = path.into_inner();
+    let mut alerts = state.job_alerts.lock().unwrap();
+    match alerts.iter().find(|a| a.id == id) {
+        Some(a) if !auth.can_access(&a.user_id) => return forbidden(),
+        Some(_) => {}
+        None => return HttpResponse::NotFound().json(ApiResponse::<serde_json::Value> { success: false, data: None, message: "Job alert not found".into() }),
+    }
+    alerts.retain(|a| a.id != id);
+    let _ = save_job_alerts(&alerts);
+    HttpResponse::Ok().json(ApiResponse::<serde_json::Value> { success: true, data: None, message: "Job alert deleted".into() })
+}
+
+/// Whether `job` satisfies every criterion of `alert`; the minimum score is
+/// checked against the alert owner's skills.
+fn alert_matches(alert: &JobAlert, job: &Job, user_skills: &[String], idf: &HashMap<String, f64>) -> bool {
+    let text = format!("{}\n{}\n{}", job.title, job.company, strip_html(&job.description)).to_lowercase();
+    if !alert.keywords.iter().all(|k| text.contains(&k.to_lowercase())) { return false; }
+    if let Some(c) = &alert.category {
+        if !job.category.as_deref().map(|jc| jc.eq_ignore_ascii_case(c)).unwrap_or(false) { return false; }
+    }
+    if let Some(c) = &alert.company {
+        if !job.company.to_lowercase().contains(&c.to_lowercase()) { return false; }
+    }
+    if let Some(loc) = &alert.location {
+        // Known countries and regions are matched on eligibility, anything else as text
+        match resolve_place(loc) {
+            Some(place) => {
+                if eligibility(&job_location(job), &place) != Eligibility::Eligible { return false; }
+            }
+            None => {
+                let job_loc = job.location.as_deref().unwrap_or("").to_lowercase();
+                let open_anywhere = job_loc.contains("worldwide") || job_loc.contains("anywhere");
+                if !open_anywhere && !job_loc.contains(&loc.to_lowercase()) { return false; }
+            }
+        }
+    }
+    if let Some(min) = alert.min_match_score {
+        let (score, _, _) = compute_weighted_match(user_skills, &text, idf);
+        if score < min { return false; }
+    }
+    true
+}
+
+/// Run every enabled alert against jobs seen in a sync and notify owners of new matches.
+/// Announced ids of jobs that left the catalog are dropped, so the list stays bounded.
+pub fn evaluate_job_alerts(state: &AppState, jobs: &[Job]) {
+    let listed_ids: HashSet<String> = state.jobs_cache.lock().unwrap().iter().filter(|j| j.is_listed()).map(|j| j.id.clone()).collect();
+    let skills_by_user: HashMap<String, Vec<String>> = state.users.lock().unwrap().iter().map(|u| (u.id.clone(), u.skills.clone())).collect();
+    let idf = state.idf.lock().unwrap().clone();
+
+    let mut pending: Vec<(JobAlert, Vec<&Job>)> = vec![];
+    {
+        let mut alerts = state.job_alerts.lock().unwrap();
+        let now = Utc::now().to_rfc3339();
+        let mut pruned = false;
+        for alert in alerts.iter_mut() {
+            let before = alert.notified_job_ids.len();
+            alert.notified_job_ids.retain(|id| listed_ids.contains(id));
+            pruned |= alert.notified_job_ids.len() != before;
+        }
+        for alert in alerts.iter_mut().filter(|a| a.enabled) {
+            let skills = match skills_by_user.get(&alert.user_id) { Some(s) => s, None => continue };
+            // Job ids are derived from source and external id, so equal external ids from different sources stay apart
+            let hits: Vec<&Job> = jobs.iter()
+                .filter(|j| !alert.notified_job_ids.contains(&j.id))
+                .filter(|j| alert_matches(alert, j, skills, &idf))
+                .collect();
+            if hits.is_empty() { continue; }
+            alert.notified_job_ids.extend(hits.iter().map(|j| j.id.clone()));
+            alert.last_triggered_at = Some(now.clone());
+            pending.push((alert.clone(), hits));
+        }
+        if pruned || !pending.is_empty() { let _ = save_job_alerts(&alerts); }
+    }
+
+    for (alert, hits) in pending {
+        let title = format!("{}: {} new job{}", alert.name, hits.len(), if hits.len() == 1 { "" } else { "s" });
+        let listed: Vec<String> = hits.iter().take(3).map(|j| format!("{} at {}", j.title, j.company)).collect();
+        let mut description = listed.join(", ");
+        if hits.len() > listed.len() { description.push_str(&format!(" and {} more", hits.len() - listed.len())); }
+        let job_ids: Vec<String> = hits.iter().map(|j| j.id.clone()).collect();
+        push_notification(state, &alert.user_id, NotificationKind::JobAlert, title, description, Some(serde_json::json!({ "alert_id": alert.id, "job_ids": job_ids })));
+    }
+}
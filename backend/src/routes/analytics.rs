@@ -0,0 +1,125 @@
+use actix_web::{web, HttpResponse};
+use chrono::{DateTime, Duration, Utc};
+use std::collections::{HashMap, HashSet};
+
+use crate::models::ApiResponse;
+use crate::state::AppState;
+use crate::utils::auth::{forbidden, AuthUser};
+use crate::utils::matching::{weighted_match_tokens, COMMON_TECH_SKILLS};
+use crate::utils::sessions::active_session_count;
+use crate::utils::text::{normalize_token, strip_html, tokenize};
+
+const SCORE_BUCKETS: [&str; 5] = ["0-20", "20-40", "40-60", "60-80", "80-100"];
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.route("/analytics/metrics", web::get().to(get_metrics));
+}
+
+fn is_recent(ts: &str, cutoff: DateTime<Utc>) -> bool {
+    DateTime::parse_from_rfc3339(ts).map(|t| t.with_timezone(&Utc) >= cutoff).unwrap_or(false)
+}
+
+fn ratio(part: u64, other: u64) -> f64 {
+    if part + other == 0 { 0.0 } else { part as f64 / (part + other) as f64 }
+}
+
+async fn get_metrics(state: web::Data<AppState>, auth: AuthUser) -> HttpResponse {
+    if !auth.is_admin() { return forbidden(); }
+    let users = state.users.lock().unwrap().clone();
+    let jobs: Vec<_> = state.jobs_cache.lock().unwrap().iter().filter(|j| j.is_listed()).cloned().collect();
+    let applications = state.applications.lock().unwrap().clone();
+    let feedback = state.feedback.lock().unwrap().clone();
+    let usage = state.metrics.lock().unwrap().clone();
+    let active_sessions = active_session_count(&state);
+
+    // A user is active if they touched their profile, an application or feedback recently
+    let now = Utc::now();
+    let mut last_seen: HashMap<&str, Vec<&str>> = HashMap::new();
+    for u in users.iter() { last_seen.entry(u.id.as_str()).or_default().push(u.updated_at.as_str()); }
+    for a in applications.iter() { last_seen.entry(a.user_id.as_str()).or_default().push(a.updated_at.as_str()); }
+    for f in feedback.iter() { last_seen.entry(f.user_id.as_str()).or_default().push(f.timestamp.as_str()); }
+    let active_within = |days: i64| {
+        let cutoff = now - Duration::days(days);
+        last_seen.values().filter(|ts| ts.iter().any(|t| is_recent(t, cutoff))).count()
+    };
+
+    let profile_resumes = users.iter().filter(|u| u.resume_filename.is_some()).count();
+    let application_resumes: usize = applications.iter().map(|a| a.resume_versions.len()).sum();
+    let files_on_disk = std::fs::read_dir(&state.uploads_dir).map(|d| d.filter_map(|e| e.ok()).count()).unwrap_or(0);
+
+    // Tokenized once; every user below is scored against these
+    let job_tokens: Vec<HashSet<String>> = jobs.iter()
+        .map(|j| tokenize(&format!("{}\n{}", j.title, strip_html(&j.description))).into_iter().collect())
+        .collect();
+
+    // Score every user with skills against every cached job, as recommendations would
+    let idf = state.idf.lock().unwrap().clone();
+    let mut buckets: Vec<usize> = vec![0; SCORE_BUCKETS.len()];
+    let mut score_sum = 0.0;
+    let mut score_count = 0usize;
+    let mut per_user_best: Vec<f64> = vec![];
+    for u in users.iter().filter(|u| !u.skills.is_empty()) {
+        let mut best = 0.0_f64;
+        for tokens in job_tokens.iter() {
+            let (score, _, _) = weighted_match_tokens(&u.skills, tokens, &idf);
+            let idx = ((score / 20.0) as usize).min(SCORE_BUCKETS.len() - 1);
+            buckets[idx] += 1;
+            score_sum += score;
+            score_count += 1;
+            best = best.max(score);
+        }
+        if !jobs.is_empty() { per_user_best.push(best); }
+    }
+    let distribution: HashMap<&str, usize> = SCORE_BUCKETS.iter().copied().zip(buckets).collect();
+
+    // Skill vocabulary: everything users list plus the common tech skills
+    let mut vocabulary: HashSet<String> = COMMON_TECH_SKILLS.iter().map(|s| normalize_token(s)).collect();
+    for u in users.iter() { for s in u.skills.iter() { vocabulary.insert(normalize_token(s)); } }
+    let mut demand: Vec<(String, usize)> = vocabulary.into_iter()
+        .filter(|s| !s.is_empty())
+        .map(|s| { let n = job_tokens.iter().filter(|t| t.contains(&s)).count(); (s, n) })
+        .filter(|(_, n)| *n > 0)
+        .collect();
+    demand.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    demand.truncate(15);
+
+    let data = serde_json::json!({
+        "generated_at": now.to_rfc3339(),
+        "counters_since": state.started_at,
+        "users": {
+            "total": users.len(),
+            "active_7d": active_within(7),
+            "active_30d": active_within(30),
+            "active_sessions": active_sessions,
+        },
+        "resumes": {
+            "profile_resumes": profile_resumes,
+            "application_resumes": application_resumes,
+            "files_stored": files_on_disk,
+        },
+        "recommendations": {
+            "requests": usage.recommendation_requests,
+            "served": usage.recommendations_served,
+        },
+        "match_scores": {
+            "jobs_scored": jobs.len(),
+            "pairs_scored": score_count,
+            "average": if score_count > 0 { (score_sum / score_count as f64 * 10.0).round() / 10.0 } else { 0.0 },
+            "average_best_per_user": if per_user_best.is_empty() { 0.0 } else { (per_user_best.iter().sum::<f64>() / per_user_best.len() as f64 * 10.0).round() / 10.0 },
+            "distribution": distribution,
+        },
+        "top_skills": demand.into_iter().map(|(skill, jobs)| serde_json::json!({ "skill": skill, "jobs": jobs })).collect::<Vec<_>>(),
+        "ml_service": {
+            "resume_ml": usage.resume_ml_matches,
+            "resume_fallback": usage.resume_fallback_matches,
+            "resume_ml_ratio": ratio(usage.resume_ml_matches, usage.resume_fallback_matches),
+            "job_ml": usage.job_ml_analyses,
+            "job_fallback": usage.job_fallback_analyses,
+            "job_ml_ratio": ratio(usage.job_ml_analyses, usage.job_fallback_analyses),
+        },
+        "applications": applications.len(),
+        "feedback": feedback.len(),
+    });
+
+    HttpResponse::Ok().json(ApiResponse { success: true, data: Some(data), message: "Platform metrics".into() })
+}
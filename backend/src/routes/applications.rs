@@ -0,0 +1,312 @@
+use actix_multipart::Multipart;
+use actix_web::{web, HttpResponse};
+use chrono::Utc;
+use serde::Deserialize;
+use std::fs;
+use uuid::Uuid;
+
+use crate::models::{ApiResponse, Application, ApplicationStatus, ApplicationStatusChange, NotificationKind, ResumeVersion};
+use crate::routes::notifications::push_notification;
+use crate::state::{AppState, save_applications};
+use crate::storage::save_failed;
+use crate::utils::auth::{forbidden, AuthUser};
+use crate::utils::insights::compute_insights;
+use crate::utils::stats::compute_application_stats;
+use crate::utils::uploads::{save_multipart_file, serve_resume_file};
+
+#[derive(Deserialize)]
+pub struct CreateApplicationPayload {
+    pub job_id: String,
+    pub cover_letter: Option<String>,
+    pub match_score: Option<f64>,
+    pub job_title: Option<String>,
+    pub company_name: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct UpdateStatusPayload {
+    pub status: String,
+    pub note: Option<String>,
+}
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg
+        .route("/applications/users/{user_id}", web::post().to(create_application))
+        .route("/applications/users/{user_id}", web::get().to(get_user_applications))
+        .route("/users/{user_id}/applications/stats", web::get().to(get_user_application_stats))
+        .route("/applications/{id}", web::get().to(get_application))
+        .route("/applications/{id}/status", web::put().to(update_application_status))
+        .route("/applications/{id}/insights", web::get().to(get_application_insights))
+        .route("/applications/{id}/resume", web::post().to(upload_application_resume))
+        .route("/applications/{id}/resume", web::get().to(get_application_resume))
+        .route("/applications/{id}/resume/versions", web::get().to(list_resume_versions))
+        .route("/applications/{id}/resume/versions/{version}", web::get().to(get_resume_version));
+}
+
+/// 404 for an unknown application, 403 when it belongs to someone else.
+fn check_application_access(state: &AppState, auth: &AuthUser, id: &str) -> Result<(), HttpResponse> {
+    match state.applications.lock().unwrap().iter().find(|a| a.id == id) {
+        Some(a) if auth.can_access(&a.user_id) => Ok(()),
+        Some(_) => Err(forbidden()),
+        None => Err(HttpResponse::NotFound().json(ApiResponse::<serde_json::Value> { success: false, data: None, message: "Application not found".into() })),
+    }
+}
+
+async fn create_application(state: web::Data<AppState>, auth: AuthUser, path: web::Path<String>, payload: web::Json<CreateApplicationPayload>) -> HttpResponse {
+    let user_id = path.into_inner();
+    if !auth.can_access(&user_id) { return forbidden(); }
+    let job_id = payload.job_id.trim().to_string();
+    if job_id.is_empty() {
+        return HttpResponse::BadRequest().json(ApiResponse::<serde_json::Value> { success: false, data: None, message: "job_id is required".into() });
+    }
+    let profile_resume = {
+        let users = state.users.lock().unwrap();
+        match users.iter().find(|u| u.id == user_id) {
+            Some(u) => u.resume_filename.clone(),
+            None => return HttpResponse::NotFound().json(ApiResponse::<serde_json::Value> { success: false, data: None, message: "User not found".into() }),
+        }
+    };
+
+    // Fill in job details from the cache when the client did not send them
+    let cached_job = {
+        let jobs = state.jobs_cache.lock().unwrap();
+        jobs.iter().find(|j| j.id == job_id || j.external_id.as_deref() == Some(job_id.as_str())).cloned()
+    };
+
+    let mut applications = state.applications.lock().unwrap();
+    if applications.iter().any(|a| a.user_id == user_id && a.job_id == job_id && a.status != ApplicationStatus::Withdrawn) {
+        return HttpResponse::BadRequest().json(ApiResponse::<serde_json::Value> { success: false, data: None, message: "Already applied to this job".into() });
+    }
+
+    let now = Utc::now().to_rfc3339();
+    // Pin whatever resume is on the profile right now; later profile uploads don't affect it
+    let pinned = profile_resume.and_then(|f| profile_resume_version(&state, &f, &now));
+    let application = Application {
+        id: Uuid::new_v4().to_string(),
+        user_id,
+        job_id,
+        status: ApplicationStatus::Pending,
+        applied_at: now.clone(),
+        updated_at: now.clone(),
+        match_score: payload.match_score,
+        job_title: payload.job_title.clone().or_else(|| cached_job.as_ref().map(|j| j.title.clone())),
+        company_name: payload.company_name.clone().or_else(|| cached_job.as_ref().map(|j| j.company.clone())),
+        job_category: cached_job.as_ref().and_then(|j| j.category.clone()),
+        cover_letter: payload.cover_letter.clone(),
+        status_history: vec![ApplicationStatusChange { status: ApplicationStatus::Pending, changed_at: now.clone(), note: None }],
+        resume_filename: pinned.as_ref().map(|v| v.filename.clone()),
+        resume_uploaded_at: pinned.as_ref().map(|v| v.uploaded_at.clone()),
+        resume_versions: pinned.into_iter().collect(),
+    };
+    applications.push(application.clone());
+    let _ = save_applications(&applications);
+
+    HttpResponse::Ok().json(ApiResponse { success: true, data: Some(application), message: "Application created".into() })
+}
+
+async fn get_user_applications(state: web::Data<AppState>, auth: AuthUser, path: web::Path<String>) -> HttpResponse {
+    let user_id = path.into_inner();
+    if !auth.can_access(&user_id) { return forbidden(); }
+    let applications = state.applications.lock().unwrap();
+    let mut results: Vec<Application> = applications.iter().filter(|a| a.user_id == user_id).cloned().collect();
+    results.sort_by(|a, b| b.applied_at.cmp(&a.applied_at));
+    HttpResponse::Ok().json(ApiResponse { success: true, data: Some(results), message: "Applications fetched".into() })
+}
+
+async fn get_user_application_stats(state: web::Data<AppState>, auth: AuthUser, path: web::Path<String>) -> HttpResponse {
+    let user_id = path.into_inner();
+    if !auth.can_access(&user_id) { return forbidden(); }
+    if !state.users.lock().unwrap().iter().any(|u| u.id == user_id) {
+        return HttpResponse::NotFound().json(ApiResponse::<serde_json::Value> { success: false, data: None, message: "User not found".into() });
+    }
+    let mine: Vec<Application> = state.applications.lock().unwrap().iter().filter(|a| a.user_id == user_id).cloned().collect();
+    HttpResponse::Ok().json(ApiResponse { success: true, data: Some(compute_application_stats(&mine)), message: "Application statistics".into() })
+}
+
+async fn get_application(state: web::Data<AppState>, auth: AuthUser, path: web::Path<String>) -> HttpResponse {
+    let id = path.into_inner();
+    if let Err(resp) = check_application_access(&state, &auth, &id) { return resp; }
+    let applications = state.applications.lock().unwrap();
+    if let Some(a) = applications.iter().find(|a| a.id == id) {
+        return HttpResponse::Ok().json(ApiResponse { success: true, data: Some(a.clone()), message: "Application fetched".into() });
+    }
+    HttpResponse::NotFound().json(ApiResponse::<serde_json::Value> { success: false, data: None, message: "Application not found".into() })
+}
+
+async fn update_application_status(state: web::Data<AppState>, auth: AuthUser, path: web::Path<String>, payload: web::Json<UpdateStatusPayload>) -> HttpResponse {
+    let id = path.into_inner();
+    if let Err(resp) = check_application_access(&state, &auth, &id) { return resp; }
+    let next = match ApplicationStatus::parse(&payload.status) {
+        Some(s) => s,
+        None => return HttpResponse::BadRequest().json(ApiResponse::<serde_json::Value> { success: false, data: None, message: format!("Unknown status: {}", payload.status) }),
+    };
+
+    let mut applications = state.applications.lock().unwrap();
+    let app = match applications.iter_mut().find(|a| a.id == id) {
+        Some(a) => a,
+        None => return HttpResponse::NotFound().json(ApiResponse::<serde_json::Value> { success: false, data: None, message: "Application not found".into() }),
+    };
+    if !app.status.can_transition_to(next) {
+        return HttpResponse::BadRequest().json(ApiResponse::<serde_json::Value> {
+            success: false,
+            data: None,
+            message: format!("Cannot change status from {} to {}", app.status.as_str(), next.as_str()),
+        });
+    }
+
+    let now = Utc::now().to_rfc3339();
+    app.status = next;
+    app.updated_at = now.clone();
+    app.status_history.push(ApplicationStatusChange { status: next, changed_at: now, note: payload.note.clone() });
+    let updated = app.clone();
+    let _ = save_applications(&applications);
+    drop(applications);
+
+    let role = match (&updated.job_title, &updated.company_name) {
+        (Some(t), Some(c)) => format!("{} at {}", t, c),
+        (Some(t), None) => t.clone(),
+        _ => "your application".to_string(),
+    };
+    push_notification(
+        &state,
+        &updated.user_id,
+        NotificationKind::ApplicationStatus,
+        "Application status update".to_string(),
+        format!("Your application for {} is now {}.", role, next.as_str().replace('_', " ")),
+        Some(serde_json::json!({ "application_id": updated.id, "job_id": updated.job_id, "status": next })),
+    );
+
+    HttpResponse::Ok().json(ApiResponse { success: true, data: Some(updated), message: "Application status updated".into() })
+}
+
+async fn get_application_insights(state: web::Data<AppState>, auth: AuthUser, path: web::Path<String>) -> HttpResponse {
+    let id = path.into_inner();
+    if let Err(resp) = check_application_access(&state, &auth, &id) { return resp; }
+    let all = state.applications.lock().unwrap().clone();
+    let app = match all.iter().find(|a| a.id == id) {
+        Some(a) => a.clone(),
+        None => return HttpResponse::NotFound().json(ApiResponse::<serde_json::Value> { success: false, data: None, message: "Application not found".into() }),
+    };
+    let users = state.users.lock().unwrap().clone();
+    let user = match users.iter().find(|u| u.id == app.user_id) {
+        Some(u) => u.clone(),
+        None => return HttpResponse::NotFound().json(ApiResponse::<serde_json::Value> { success: false, data: None, message: "User not found".into() }),
+    };
+    let job = {
+        let jobs = state.jobs_cache.lock().unwrap();
+        jobs.iter().find(|j| j.id == app.job_id || j.external_id.as_deref() == Some(app.job_id.as_str())).cloned()
+    };
+
+    let idf = state.idf.lock().unwrap();
+    let insights = compute_insights(&app, &user, job.as_ref(), &all, &users, &idf);
+    HttpResponse::Ok().json(ApiResponse { success: true, data: Some(insights), message: "Application insights".into() })
+}
+
+fn profile_resume_version(state: &AppState, filename: &str, now: &str) -> Option<ResumeVersion> {
+    let full = state.uploads_dir.join(filename);
+    let meta = fs::metadata(&full).ok()?;
+    Some(ResumeVersion {
+        version: 1,
+        filename: filename.to_string(),
+        original_filename: filename.to_string(),
+        content_type: mime_guess::from_path(&full).first_or_octet_stream().to_string(),
+        size_bytes: meta.len(),
+        uploaded_at: now.to_string(),
+    })
+}
+
+async fn upload_application_resume(state: web::Data<AppState>, auth: AuthUser, path: web::Path<String>, mut payload: Multipart) -> Result<HttpResponse, actix_web::Error> {
+    let id = path.into_inner();
+    if let Err(resp) = check_application_access(&state, &auth, &id) { return Ok(resp); }
+
+    // Stored under a unique name, then renamed for its version once that is picked under the lock
+    let pending = format!("app_{}_pending_{}", id, Uuid::new_v4().simple());
+    let mut upload = match save_multipart_file(&mut payload, &state.uploads_dir, &pending).await? {
+        Some(u) => u,
+        None => return Ok(HttpResponse::BadRequest().json(ApiResponse::<serde_json::Value> { success: false, data: None, message: "No file received".into() })),
+    };
+
+    let (version, recorded) = {
+        let mut applications = state.applications.lock().unwrap();
+        let app = match applications.iter_mut().find(|a| a.id == id) {
+            Some(a) => a,
+            None => {
+                let _ = fs::remove_file(&upload.path);
+                return Ok(HttpResponse::NotFound().json(ApiResponse::<serde_json::Value> { success: false, data: None, message: "Application not found".into() }));
+            }
+        };
+        let now = Utc::now().to_rfc3339();
+        let version_number = app.resume_versions.iter().map(|v| v.version).max().unwrap_or(0) + 1;
+        if let Err(e) = upload.set_prefix(&format!("app_{}_v{}", id, version_number)) {
+            println!("❌ Failed to store resume upload: {}", e);
+            let _ = fs::remove_file(&upload.path);
+            return Ok(HttpResponse::InternalServerError().json(ApiResponse::<serde_json::Value> { success: false, data: None, message: "Failed to store the resume".into() }));
+        }
+        let recorded = state.storage.record_resume(upload.resume_record(&app.user_id, Some(&app.id), Some(version_number)));
+        let version = ResumeVersion {
+            version: version_number,
+            filename: upload.filename,
+            original_filename: upload.original_filename,
+            content_type: upload.content_type,
+            size_bytes: upload.size_bytes,
+            uploaded_at: now.clone(),
+        };
+        app.resume_filename = Some(version.filename.clone());
+        app.resume_uploaded_at = Some(now.clone());
+        app.resume_versions.push(version.clone());
+        app.updated_at = now;
+        let _ = save_applications(&applications);
+        (version, recorded)
+    };
+    if let Err(e) = recorded.await { return Ok(save_failed(e)); }
+
+    let meta = serde_json::json!({ "filename": version.filename, "uploaded_at": version.uploaded_at, "version": version.version });
+    Ok(HttpResponse::Ok().json(ApiResponse { success: true, data: Some(meta), message: "Resume uploaded".into() }))
+}
+
+async fn get_application_resume(state: web::Data<AppState>, auth: AuthUser, path: web::Path<String>) -> HttpResponse {
+    let id = path.into_inner();
+    if let Err(resp) = check_application_access(&state, &auth, &id) { return resp; }
+    let current = {
+        let applications = state.applications.lock().unwrap();
+        applications.iter().find(|a| a.id == id).and_then(|a| {
+            let pinned = a.resume_filename.as_ref()?;
+            a.resume_versions.iter().find(|v| &v.filename == pinned).cloned()
+        })
+    };
+    match current {
+        Some(v) => serve_resume_version(&state, &v),
+        None => HttpResponse::NotFound().finish(),
+    }
+}
+
+async fn list_resume_versions(state: web::Data<AppState>, auth: AuthUser, path: web::Path<String>) -> HttpResponse {
+    let id = path.into_inner();
+    if let Err(resp) = check_application_access(&state, &auth, &id) { return resp; }
+    let applications = state.applications.lock().unwrap();
+    if let Some(a) = applications.iter().find(|a| a.id == id) {
+        return HttpResponse::Ok().json(ApiResponse { success: true, data: Some(a.resume_versions.clone()), message: "Resume versions fetched".into() });
+    }
+    HttpResponse::NotFound().json(ApiResponse::<serde_json::Value> { success: false, data: None, message: "Application not found".into() })
+}
+
+async fn get_resume_version(state: web::Data<AppState>, auth: AuthUser, path: web::Path<(String, u32)>) -> HttpResponse {
+    let (id, version) = path.into_inner();
+    if let Err(resp) = check_application_access(&state, &auth, &id) { return resp; }
+    let found = {
+        let applications = state.applications.lock().unwrap();
+        applications.iter().find(|a| a.id == id).and_then(|a| a.resume_versions.iter().find(|v| v.version == version).cloned())
+    };
+    match found {
+        Some(v) => serve_resume_version(&state, &v),
+        None => HttpResponse::NotFound().finish(),
+    }
+}
+
+fn serve_resume_version(state: &AppState, version: &ResumeVersion) -> HttpResponse {
+    let full = state.uploads_dir.join(&version.filename);
+    match fs::read(&full) {
+        Ok(data) => serve_resume_file(&version.filename, &version.original_filename, data),
+        Err(_) => HttpResponse::NotFound().finish(),
+    }
+}
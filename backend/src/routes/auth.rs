@@ -1,13 +1,18 @@
 use actix_web::{web, HttpResponse};
-use chrono::Utc;
+use chrono::{DateTime, Duration, Utc};
+use rand::Rng;
 use serde::Deserialize;
 use uuid::Uuid;
 use bcrypt::{hash, verify as bcrypt_verify, DEFAULT_COST};
-use base64::{engine::general_purpose::STANDARD, Engine as _};
 
-use crate::models::{ApiResponse, AuthResponse, User};
-use crate::state::{AppState, save_users};
-use crate::utils::jwt::{make_tokens, verify_token};
+use crate::models::{ApiResponse, AuthResponse, PasswordResetRecord, Role, User};
+use crate::state::AppState;
+use crate::storage::save_failed;
+use crate::utils::auth::AuthUser;
+use crate::utils::google::GoogleError;
+use crate::utils::jwt::{hash_token, verify_token, TokenType};
+use crate::utils::mail::OutgoingMail;
+use crate::utils::sessions::{end_all_sessions, end_session, rotate, session_active, start_session, RotateError};
 
 #[derive(Deserialize)]
 pub struct RegisterPayload {
@@ -25,6 +30,19 @@ pub struct VerifyPayload { pub token: String }
 pub struct RefreshPayload { pub refresh_token: String }
 #[derive(Deserialize)]
 pub struct GooglePayload { pub id_token: String }
+#[derive(Deserialize)]
+pub struct ForgotPasswordPayload { pub email: String }
+#[derive(Deserialize)]
+pub struct ResetPasswordPayload { pub email: String, pub token: String, pub new_password: String }
+
+const MIN_PASSWORD_LEN: usize = 8;
+// A new reset email for the same account is sent at most this often
+const RESET_RESEND_SECS: i64 = 60;
+// Crockford base32: no I, L, O or U, so a code survives being read off one screen and typed on another
+const RESET_CODE_ALPHABET: &[u8] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+const RESET_CODE_LEN: usize = 8;
+// Wrong codes an account may enter before its current code stops working
+const MAX_RESET_ATTEMPTS: u32 = 5;
 
 pub fn config(cfg: &mut web::ServiceConfig) {
     cfg
@@ -32,54 +50,75 @@ pub fn config(cfg: &mut web::ServiceConfig) {
         .route("/auth/login", web::post().to(login))
         .route("/auth/verify", web::post().to(verify_token_handler))
         .route("/auth/refresh", web::post().to(refresh))
-        .route("/auth/google", web::post().to(google));
+        .route("/auth/logout", web::post().to(logout))
+        .route("/auth/logout-all", web::post().to(logout_all))
+        .route("/auth/google", web::post().to(google))
+        .route("/auth/password/forgot", web::post().to(forgot_password))
+        .route("/auth/password/reset", web::post().to(reset_password));
 }
 
 async fn register(state: web::Data<AppState>, payload: web::Json<RegisterPayload>) -> HttpResponse {
-    let mut users = state.users.lock().unwrap();
-    if users.iter().any(|u| u.email.eq_ignore_ascii_case(&payload.email)) {
-        return HttpResponse::BadRequest().json(ApiResponse::<serde_json::Value> { success: false, data: None, message: "Email already registered".into() });
-    }
-    let now = Utc::now().to_rfc3339();
-    let user = User {
-        id: Uuid::new_v4().to_string(),
-        email: payload.email.trim().to_lowercase(),
-        password_hash: Some(hash(&payload.password, DEFAULT_COST).unwrap()),
-        first_name: payload.first_name.trim().to_string(),
-        last_name: payload.last_name.trim().to_string(),
-        skills: vec![],
-        experience_years: 0,
-        location: "".into(),
-        created_at: now.clone(),
-        updated_at: now.clone(),
-        resume_filename: None,
+    let email = payload.email.trim().to_lowercase();
+    let (user, saved) = {
+        let mut users = state.users.lock().unwrap();
+        if users.iter().any(|u| u.email.eq_ignore_ascii_case(&email)) {
+            return HttpResponse::BadRequest().json(ApiResponse::<serde_json::Value> { success: false, data: None, message: "Email already registered".into() });
+        }
+        let now = Utc::now().to_rfc3339();
+        let user = User {
+            id: Uuid::new_v4().to_string(),
+            // Admins come from ADMIN_EMAILS at startup or the role API, never from sign-up
+            role: Role::User,
+            email,
+            password_hash: Some(hash(&payload.password, DEFAULT_COST).unwrap()),
+            first_name: payload.first_name.trim().to_string(),
+            last_name: payload.last_name.trim().to_string(),
+            skills: vec![],
+            experience_years: 0,
+            location: "".into(),
+            created_at: now.clone(),
+            updated_at: now.clone(),
+            resume_filename: None,
+            google_sub: None,
+        };
+        users.push(user.clone());
+        let saved = state.storage.save_users(&users, &[user.id.as_str()]);
+        (user, saved)
     };
-    users.push(user.clone());
-    let _ = save_users(&users);
+    if let Err(e) = saved.await { return save_failed(e); }
 
-    let (access, refresh_tok, exp, refresh_exp) = make_tokens(&user.id, &state.jwt_secret);
-    state.refresh_tokens.lock().unwrap().insert(refresh_tok.clone(), user.id.clone());
+    let tokens = match start_session(&state, &user.id, user.role).await {
+        Ok(t) => t,
+        Err(e) => return save_failed(e),
+    };
 
     let mut user_resp = user.clone(); user_resp.password_hash = None;
     HttpResponse::Ok().json(ApiResponse { success: true, data: Some(AuthResponse {
-        access_token: access,
-        refresh_token: refresh_tok,
+        access_token: tokens.access_token,
+        refresh_token: tokens.refresh_token,
         user: user_resp,
-        expires_at: exp,
-        refresh_expires_at: refresh_exp,
+        expires_at: tokens.expires_at,
+        refresh_expires_at: tokens.refresh_expires_at,
     }), message: "Registered successfully".into() })
 }
 
 async fn login(state: web::Data<AppState>, payload: web::Json<LoginPayload>) -> HttpResponse {
-    let users = state.users.lock().unwrap();
-    if let Some(user) = users.iter().find(|u| u.email.eq_ignore_ascii_case(&payload.email)) {
+    let email = payload.email.trim();
+    let user = state.users.lock().unwrap().iter().find(|u| u.email.eq_ignore_ascii_case(email)).cloned();
+    if let Some(user) = user {
         if let Some(ph) = &user.password_hash {
             if bcrypt_verify(&payload.password, ph).unwrap_or(false) {
-                let (access, refresh_tok, exp, refresh_exp) = make_tokens(&user.id, &state.jwt_secret);
-                state.refresh_tokens.lock().unwrap().insert(refresh_tok.clone(), user.id.clone());
+                let tokens = match start_session(&state, &user.id, user.role).await {
+                    Ok(t) => t,
+                    Err(e) => return save_failed(e),
+                };
                 let mut user_resp = user.clone(); user_resp.password_hash = None;
                 return HttpResponse::Ok().json(ApiResponse { success: true, data: Some(AuthResponse {
-                    access_token: access, refresh_token: refresh_tok, user: user_resp, expires_at: exp, refresh_expires_at: refresh_exp,
+                    access_token: tokens.access_token,
+                    refresh_token: tokens.refresh_token,
+                    user: user_resp,
+                    expires_at: tokens.expires_at,
+                    refresh_expires_at: tokens.refresh_expires_at,
                 }), message: "Login successful".into() });
             }
         }
@@ -88,84 +127,404 @@ async fn login(state: web::Data<AppState>, payload: web::Json<LoginPayload>) ->
 }
 
 async fn verify_token_handler(state: web::Data<AppState>, payload: web::Json<VerifyPayload>) -> HttpResponse {
-    match verify_token(&payload.token, &state.jwt_secret) {
+    match verify_token(&payload.token, &state.jwt_secret, TokenType::Access) {
+        Ok(claims) if !session_active(&state, &claims.sid) => HttpResponse::Unauthorized().json(ApiResponse::<serde_json::Value> { success: false, data: None, message: "Session has ended".into() }),
         Ok(_) => HttpResponse::Ok().json(ApiResponse::<serde_json::Value> { success: true, data: None, message: "Token valid".into() }),
         Err(msg) => HttpResponse::Unauthorized().json(ApiResponse::<serde_json::Value> { success: false, data: None, message: msg }),
     }
 }
 
 async fn refresh(state: web::Data<AppState>, payload: web::Json<RefreshPayload>) -> HttpResponse {
-    match verify_token(&payload.refresh_token, &state.jwt_secret) {
-        Ok(user_id) => {
-            let map = state.refresh_tokens.lock().unwrap();
-            let valid = map.contains_key(&payload.refresh_token);
-            drop(map);
-            if !valid {
-                return HttpResponse::Unauthorized().json(ApiResponse::<serde_json::Value> { success: false, data: None, message: "Invalid refresh token".into() });
-            }
-            let (access, refresh_tok, exp, refresh_exp) = make_tokens(&user_id, &state.jwt_secret);
-            state.refresh_tokens.lock().unwrap().insert(refresh_tok.clone(), user_id.clone());
+    match verify_token(&payload.refresh_token, &state.jwt_secret, TokenType::Refresh) {
+        Ok(claims) => {
+            let user_id = claims.sub;
+            // The role is re-read so promotions and demotions apply from the next refresh
+            let role = match state.users.lock().unwrap().iter().find(|u| u.id == user_id) {
+                Some(u) => u.role,
+                None => return HttpResponse::Unauthorized().json(ApiResponse::<serde_json::Value> { success: false, data: None, message: "User no longer exists".into() }),
+            };
+            let tokens = match rotate(&state, &payload.refresh_token, role).await {
+                Ok(t) => t,
+                Err(RotateError::Unknown) => return HttpResponse::Unauthorized().json(ApiResponse::<serde_json::Value> { success: false, data: None, message: "Invalid refresh token".into() }),
+                Err(RotateError::Reused) => return HttpResponse::Unauthorized().json(ApiResponse::<serde_json::Value> {
+                    success: false,
+                    data: None,
+                    message: "Refresh token has already been used or revoked; please sign in again".into(),
+                }),
+                Err(RotateError::Unsaved(e)) => return save_failed(e),
+            };
             HttpResponse::Ok().json(ApiResponse { success: true, data: Some(serde_json::json!({
-                "access_token": access,
-                "refresh_token": refresh_tok,
-                "expires_at": exp,
-                "refresh_expires_at": refresh_exp,
+                "access_token": tokens.access_token,
+                "refresh_token": tokens.refresh_token,
+                "expires_at": tokens.expires_at,
+                "refresh_expires_at": tokens.refresh_expires_at,
             })), message: "Token refreshed".into() })
         }
         Err(msg) => HttpResponse::Unauthorized().json(ApiResponse::<serde_json::Value> { success: false, data: None, message: msg }),
     }
 }
 
+/// End the session the refresh token belongs to. Its access tokens stop working too.
+async fn logout(state: web::Data<AppState>, payload: web::Json<RefreshPayload>) -> HttpResponse {
+    if let Err(msg) = verify_token(&payload.refresh_token, &state.jwt_secret, TokenType::Refresh) {
+        return HttpResponse::Unauthorized().json(ApiResponse::<serde_json::Value> { success: false, data: None, message: msg });
+    }
+    let ended = match end_session(&state, &payload.refresh_token).await {
+        Ok(n) => n,
+        Err(e) => return save_failed(e),
+    };
+    HttpResponse::Ok().json(ApiResponse { success: true, data: Some(serde_json::json!({ "sessions_ended": ended })), message: "Logged out".into() })
+}
+
+/// End every session of the caller, on all devices.
+async fn logout_all(state: web::Data<AppState>, auth: AuthUser) -> HttpResponse {
+    let ended = match end_all_sessions(&state, &auth.user_id).await {
+        Ok(n) => n,
+        Err(e) => return save_failed(e),
+    };
+    println!("🔒 Ended {} session(s) for user {}", ended, auth.user_id);
+    HttpResponse::Ok().json(ApiResponse { success: true, data: Some(serde_json::json!({ "sessions_ended": ended })), message: "Logged out of all sessions".into() })
+}
+
 async fn google(state: web::Data<AppState>, payload: web::Json<GooglePayload>) -> HttpResponse {
-    // NOTE: minimal parsing; in production validate against Google.
-    let parts: Vec<&str> = payload.id_token.split('.').collect();
-    let email_opt: Option<String> = if parts.len() == 3 {
-        match STANDARD.decode(parts[1].replace('-', "+").replace('_', "/")) {
-            Ok(bytes) => match serde_json::from_slice::<serde_json::Value>(&bytes) {
-                Ok(val) => val.get("email").and_then(|e| e.as_str()).map(|s| s.to_string()),
-                Err(_) => None,
-            },
-            Err(_) => None,
+    let identity = match state.google.verify(&payload.id_token).await {
+        Ok(i) => i,
+        Err(GoogleError::NotConfigured) => return HttpResponse::ServiceUnavailable().json(ApiResponse::<serde_json::Value> { success: false, data: None, message: "Google sign-in is not configured".into() }),
+        Err(GoogleError::KeysUnavailable(msg)) => {
+            println!("❌ Google sign-in unavailable: {}", msg);
+            return HttpResponse::BadGateway().json(ApiResponse::<serde_json::Value> { success: false, data: None, message: "Could not verify the Google id_token right now".into() });
         }
-    } else { None };
-
-    let email = match email_opt { Some(e) => e.to_lowercase(), None => {
-        return HttpResponse::BadRequest().json(ApiResponse::<serde_json::Value> { success: false, data: None, message: "Invalid Google id_token".into() });
-    }};
-
-    let mut users = state.users.lock().unwrap();
-    let now = Utc::now().to_rfc3339();
-    let user = if let Some(u) = users.iter_mut().find(|u| u.email.eq_ignore_ascii_case(&email)) {
-        u.updated_at = now.clone();
-        u.clone()
-    } else {
-        let new_user = User {
-            id: Uuid::new_v4().to_string(),
-            email: email.clone(),
-            password_hash: None,
-            first_name: "".into(),
-            last_name: "".into(),
-            skills: vec![],
-            experience_years: 0,
-            location: "".into(),
-            created_at: now.clone(),
-            updated_at: now.clone(),
-            resume_filename: None,
+        Err(GoogleError::Invalid(msg)) => return HttpResponse::Unauthorized().json(ApiResponse::<serde_json::Value> { success: false, data: None, message: msg }),
+    };
+    let email = identity.email.trim().to_lowercase();
+
+    let (user, saved) = {
+        let mut users = state.users.lock().unwrap();
+        let now = Utc::now().to_rfc3339();
+        // The Google account is the identity; the email only links a first Google sign-in to an existing account
+        let user = if let Some(u) = users.iter_mut().find(|u| u.google_sub.as_deref() == Some(identity.sub.as_str())) {
+            u.updated_at = now.clone();
+            u.clone()
+        } else if let Some(u) = users.iter_mut().find(|u| u.email.eq_ignore_ascii_case(&email)) {
+            if u.google_sub.is_some() {
+                return HttpResponse::Conflict().json(ApiResponse::<serde_json::Value> { success: false, data: None, message: "This email belongs to an account linked to a different Google account".into() });
+            }
+            u.google_sub = Some(identity.sub.clone());
+            u.updated_at = now.clone();
+            let linked = u.clone();
+            println!("🔗 Linked Google account to user {}", linked.id);
+            linked
+        } else {
+            let new_user = User {
+                id: Uuid::new_v4().to_string(),
+                email: email.clone(),
+                password_hash: None,
+                first_name: identity.given_name.clone().unwrap_or_default(),
+                last_name: identity.family_name.clone().unwrap_or_default(),
+                skills: vec![],
+                experience_years: 0,
+                location: "".into(),
+                created_at: now.clone(),
+                updated_at: now.clone(),
+                resume_filename: None,
+                role: Role::User,
+                google_sub: Some(identity.sub.clone()),
+            };
+            users.push(new_user.clone());
+            new_user
         };
-        users.push(new_user.clone());
-        let _ = save_users(&users);
-        new_user
+        let saved = state.storage.save_users(&users, &[user.id.as_str()]);
+        (user, saved)
     };
+    if let Err(e) = saved.await { return save_failed(e); }
 
-    let (access, refresh_tok, exp, refresh_exp) = make_tokens(&user.id, &state.jwt_secret);
-    state.refresh_tokens.lock().unwrap().insert(refresh_tok.clone(), user.id.clone());
+    let tokens = match start_session(&state, &user.id, user.role).await {
+        Ok(t) => t,
+        Err(e) => return save_failed(e),
+    };
 
     let mut user_resp = user.clone(); user_resp.password_hash = None;
     HttpResponse::Ok().json(ApiResponse { success: true, data: Some(AuthResponse {
-        access_token: access,
-        refresh_token: refresh_tok,
+        access_token: tokens.access_token,
+        refresh_token: tokens.refresh_token,
         user: user_resp,
-        expires_at: exp,
-        refresh_expires_at: refresh_exp,
+        expires_at: tokens.expires_at,
+        refresh_expires_at: tokens.refresh_expires_at,
     }), message: "Google sign-in successful".into() })
 }
+
+fn reset_ttl() -> Duration {
+    Duration::minutes(std::env::var("PASSWORD_RESET_TTL_MINS").ok().and_then(|v| v.parse().ok()).unwrap_or(30))
+}
+
+fn reset_expired(record: &PasswordResetRecord, now: DateTime<Utc>) -> bool {
+    DateTime::parse_from_rfc3339(&record.expires_at).map(|t| t.with_timezone(&Utc) <= now).unwrap_or(true)
+}
+
+/// Unspent, unexpired and not locked by wrong guesses.
+fn reset_live(record: &PasswordResetRecord, now: DateTime<Utc>) -> bool {
+    record.used_at.is_none() && !reset_expired(record, now) && record.attempts < MAX_RESET_ATTEMPTS
+}
+
+fn new_reset_code() -> String {
+    let mut rng = rand::thread_rng();
+    (0..RESET_CODE_LEN).map(|_| RESET_CODE_ALPHABET[rng.gen_range(0..RESET_CODE_ALPHABET.len())] as char).collect()
+}
+
+/// A code as typed: any case, with or without the dash and spaces, and O, I or L for 0 and 1.
+fn normalize_reset_code(input: &str) -> String {
+    input.chars()
+        .filter(|c| !c.is_whitespace() && *c != '-')
+        .map(|c| match c.to_ascii_uppercase() { 'O' => '0', 'I' | 'L' => '1', c => c })
+        .collect()
+}
+
+// Codes are short, so the account is part of what's hashed
+fn reset_code_hash(user_id: &str, code: &str) -> String {
+    hash_token(&format!("{}:{}", user_id, code))
+}
+
+fn reset_mail_body(email: &str, code: &str) -> String {
+    let link = std::env::var("PASSWORD_RESET_URL").ok().filter(|u| !u.trim().is_empty())
+        .map(|url| format!("\nOr open this link: {}?email={}&token={}\n", url, urlencoding::encode(email), code))
+        .unwrap_or_default();
+    format!(
+        "Someone asked to reset the password of your JobLens account.\n\nReset code: {}-{}\n{}\nThe code works once and expires in {} minutes. If you didn't ask for this, ignore this email and your password stays the same.\n",
+        &code[..4], &code[4..], link, reset_ttl().num_minutes()
+    )
+}
+
+/// Issue a new reset code for `user_id`, persist it and email it. Spent and expired
+/// codes are pruned; only the newest code of an account works.
+async fn send_reset_code(state: web::Data<AppState>, user_id: String, email: String) {
+    let now = Utc::now();
+    let (code, saved) = {
+        let mut resets = state.password_resets.lock().unwrap();
+        let recently_sent = resets.iter().any(|r| {
+            r.user_id == user_id && reset_live(r, now)
+                && DateTime::parse_from_rfc3339(&r.created_at).map(|t| now - t.with_timezone(&Utc) < Duration::seconds(RESET_RESEND_SECS)).unwrap_or(false)
+        });
+        if recently_sent { return; }
+
+        let code = new_reset_code();
+        let mut changed: Vec<String> = resets.iter()
+            .filter(|r| !reset_live(r, now) || r.user_id == user_id)
+            .map(|r| r.token_hash.clone())
+            .collect();
+        resets.retain(|r| !changed.contains(&r.token_hash));
+        resets.push(PasswordResetRecord {
+            token_hash: reset_code_hash(&user_id, &code),
+            user_id: user_id.clone(),
+            created_at: now.to_rfc3339(),
+            expires_at: (now + reset_ttl()).to_rfc3339(),
+            used_at: None,
+            attempts: 0,
+        });
+        changed.push(reset_code_hash(&user_id, &code));
+        (code, state.storage.save_password_resets(&resets, &changed))
+    };
+    // A code that wasn't persisted isn't sent
+    if let Err(e) = saved.await {
+        println!("❌ Failed to persist password reset code: {}", e);
+        return;
+    }
+    let body = reset_mail_body(&email, &code);
+    let mail = OutgoingMail { to: email, subject: "Reset your JobLens password".into(), body };
+    match state.mailer.send(&mail).await {
+        Ok(()) => println!("📧 Password reset code sent for user {}", user_id),
+        Err(e) => println!("❌ Failed to send password reset email: {}", e),
+    }
+}
+
+/// Email a single-use reset code. The answer is the same, and as quick, whether or not
+/// the account exists: the code is issued, saved and sent in the background.
+async fn forgot_password(state: web::Data<AppState>, payload: web::Json<ForgotPasswordPayload>) -> HttpResponse {
+    let email = payload.email.trim().to_lowercase();
+    let user_id = state.users.lock().unwrap().iter().find(|u| u.email.eq_ignore_ascii_case(&email)).map(|u| u.id.clone());
+    if let Some(user_id) = user_id {
+        actix_web::rt::spawn(send_reset_code(state.clone(), user_id, email));
+    }
+    HttpResponse::Ok().json(ApiResponse::<serde_json::Value> {
+        success: true,
+        data: None,
+        message: "If an account exists for that email, a reset code has been sent".into(),
+    })
+}
+
+/// Set a new password with a reset code. The code is spent and every session of the
+/// account is ended, so anyone signed in with the old password is signed out.
+async fn reset_password(state: web::Data<AppState>, payload: web::Json<ResetPasswordPayload>) -> HttpResponse {
+    let invalid = || HttpResponse::BadRequest().json(ApiResponse::<serde_json::Value> { success: false, data: None, message: "Invalid or expired reset code".into() });
+    if payload.new_password.chars().count() < MIN_PASSWORD_LEN {
+        return HttpResponse::BadRequest().json(ApiResponse::<serde_json::Value> {
+            success: false,
+            data: None,
+            message: format!("Password must be at least {} characters", MIN_PASSWORD_LEN),
+        });
+    }
+
+    let email = payload.email.trim().to_lowercase();
+    let user_id = match state.users.lock().unwrap().iter().find(|u| u.email.eq_ignore_ascii_case(&email)) {
+        Some(u) => u.id.clone(),
+        None => return invalid(),
+    };
+
+    let now = Utc::now();
+    let (accepted, saved) = {
+        let mut resets = state.password_resets.lock().unwrap();
+        let record = match resets.iter_mut().find(|r| r.user_id == user_id && reset_live(r, now)) {
+            Some(r) => r,
+            None => return invalid(),
+        };
+        // A wrong code counts against the account's current one
+        let accepted = record.token_hash == reset_code_hash(&user_id, &normalize_reset_code(&payload.token));
+        if accepted { record.used_at = Some(now.to_rfc3339()); } else { record.attempts += 1; }
+        let hash = record.token_hash.clone();
+        (accepted, state.storage.save_password_resets(&resets, &[hash]))
+    };
+    if let Err(e) = saved.await { return save_failed(e); }
+    if !accepted { return invalid(); }
+
+    let (email, saved) = {
+        let mut users = state.users.lock().unwrap();
+        let user = match users.iter_mut().find(|u| u.id == user_id) {
+            Some(u) => u,
+            None => return invalid(),
+        };
+        user.password_hash = Some(hash(&payload.new_password, DEFAULT_COST).unwrap());
+        user.updated_at = now.to_rfc3339();
+        let email = user.email.clone();
+        (email, state.storage.save_users(&users, &[user_id.as_str()]))
+    };
+    if let Err(e) = saved.await { return save_failed(e); }
+    let ended = match end_all_sessions(&state, &user_id).await {
+        Ok(n) => n,
+        Err(e) => return save_failed(e),
+    };
+    println!("🔑 Password reset for user {}, ended {} session(s)", user_id, ended);
+
+    let mail = OutgoingMail {
+        to: email,
+        subject: "Your JobLens password was changed".into(),
+        body: "The password of your JobLens account was just reset and you have been signed out everywhere. If this wasn't you, reset it again right away.\n".into(),
+    };
+    if let Err(e) = state.mailer.send(&mail).await { println!("❌ Failed to send password change email: {}", e); }
+
+    HttpResponse::Ok().json(ApiResponse::<serde_json::Value> { success: true, data: None, message: "Password reset; please sign in again".into() })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::http::StatusCode;
+    use actix_web::{test, App};
+    use crate::state::scratch_state;
+    use crate::utils::mail::Mailer;
+    use crate::utils::google::testing::{claims, sign};
+
+    // POST a JSON body; evaluates to the status and the JSON reply
+    macro_rules! post {
+        ($app:expr, $path:expr, $body:expr) => {{
+            let response = test::call_service($app, test::TestRequest::post().uri($path).set_json($body).to_request()).await;
+            let status = response.status();
+            (status, test::read_body_json::<serde_json::Value, _>(response).await)
+        }};
+    }
+
+    #[actix_web::test]
+    async fn google_sign_in_links_by_subject() {
+        let state = scratch_state();
+        let app = test::init_service(App::new().app_data(state.clone()).configure(config)).await;
+        let (status, registered) = post!(&app, "/auth/register", serde_json::json!({
+            "first_name": "Ada", "last_name": "Lovelace", "email": "ada@example.com", "password": "correct horse",
+        }));
+        assert_eq!(status, StatusCode::OK);
+        let user_id = registered["data"]["user"]["id"].as_str().unwrap().to_string();
+
+        // A first Google sign-in with the account's email links it
+        let (status, body) = post!(&app, "/auth/google", serde_json::json!({ "id_token": sign(&claims("g-1", "Ada@Example.com")) }));
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["data"]["user"]["id"], user_id.as_str());
+        assert_eq!(body["data"]["user"]["google_sub"], "g-1");
+
+        // After that the subject decides, even when the Google email changes
+        let (status, body) = post!(&app, "/auth/google", serde_json::json!({ "id_token": sign(&claims("g-1", "ada@elsewhere.com")) }));
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["data"]["user"]["id"], user_id.as_str());
+
+        // Another Google account with the same email can't take the account over
+        let (status, body) = post!(&app, "/auth/google", serde_json::json!({ "id_token": sign(&claims("g-2", "ada@example.com")) }));
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert_eq!(body["success"], false);
+        assert_eq!(state.users.lock().unwrap().iter().find(|u| u.id == user_id).unwrap().google_sub.as_deref(), Some("g-1"));
+
+        // An unknown email signs up a regular user
+        let (status, body) = post!(&app, "/auth/google", serde_json::json!({ "id_token": sign(&claims("g-3", "grace@example.com")) }));
+        assert_eq!(status, StatusCode::OK);
+        assert_ne!(body["data"]["user"]["id"], user_id.as_str());
+        assert_eq!(body["data"]["user"]["role"], "user");
+        assert_eq!(state.users.lock().unwrap().len(), 2);
+
+        let _ = std::fs::remove_dir_all(&state.data_dir);
+    }
+
+    /// The newest mail spooled to `to`, waiting briefly for a background send.
+    async fn spooled_mail(state: &AppState, to: &str) -> String {
+        let dir = match &state.mailer {
+            Mailer::Spool { dir, .. } => dir.clone(),
+            Mailer::Webhook { .. } => unreachable!(),
+        };
+        for _ in 0..50 {
+            let mut mails: Vec<_> = std::fs::read_dir(&dir).into_iter().flatten().flatten().map(|e| e.path()).collect();
+            mails.sort();
+            if let Some(mail) = mails.iter().rev().map(|p| std::fs::read_to_string(p).unwrap()).find(|m| m.contains(&format!("To: {}\r\n", to))) {
+                return mail;
+            }
+            actix_web::rt::time::sleep(std::time::Duration::from_millis(20)).await;
+        }
+        panic!("no mail was spooled for {}", to);
+    }
+
+    #[actix_web::test]
+    async fn password_reset_with_the_mailed_code_ends_every_session() {
+        let state = scratch_state();
+        let app = test::init_service(App::new().app_data(state.clone()).configure(config)).await;
+        let (_, registered) = post!(&app, "/auth/register", serde_json::json!({
+            "first_name": "Ada", "last_name": "Lovelace", "email": "ada@example.com", "password": "correct horse",
+        }));
+        let (_, other_device) = post!(&app, "/auth/login", serde_json::json!({ "email": "ada@example.com", "password": "correct horse" }));
+        let sessions = [&registered["data"], &other_device["data"]];
+        for session in sessions {
+            let (status, _) = post!(&app, "/auth/verify", serde_json::json!({ "token": session["access_token"] }));
+            assert_eq!(status, StatusCode::OK);
+        }
+
+        let (status, _) = post!(&app, "/auth/password/forgot", serde_json::json!({ "email": " Ada@Example.com" }));
+        assert_eq!(status, StatusCode::OK);
+        let mail = spooled_mail(&state, "ada@example.com").await;
+        let code = regex::Regex::new(r"Reset code: ([0-9A-Z]{4}-[0-9A-Z]{4})").unwrap()
+            .captures(&mail).expect("the mail carries the code")[1].to_string();
+
+        let (status, _) = post!(&app, "/auth/password/reset", serde_json::json!({ "email": "ada@example.com", "token": "0000-0000", "new_password": "battery staple" }));
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        // Typed in lowercase on a phone, the code still works after a wrong guess
+        let (status, _) = post!(&app, "/auth/password/reset", serde_json::json!({ "email": "ada@example.com", "token": code.to_lowercase(), "new_password": "battery staple" }));
+        assert_eq!(status, StatusCode::OK);
+
+        for session in sessions {
+            let (status, _) = post!(&app, "/auth/verify", serde_json::json!({ "token": session["access_token"] }));
+            assert_eq!(status, StatusCode::UNAUTHORIZED);
+            let (status, _) = post!(&app, "/auth/refresh", serde_json::json!({ "refresh_token": session["refresh_token"] }));
+            assert_eq!(status, StatusCode::UNAUTHORIZED);
+        }
+        let (status, _) = post!(&app, "/auth/password/reset", serde_json::json!({ "email": "ada@example.com", "token": code, "new_password": "another one" }));
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let (status, _) = post!(&app, "/auth/login", serde_json::json!({ "email": "ada@example.com", "password": "correct horse" }));
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        let (status, _) = post!(&app, "/auth/login", serde_json::json!({ "email": "ada@example.com", "password": "battery staple" }));
+        assert_eq!(status, StatusCode::OK);
+        assert!(spooled_mail(&state, "ada@example.com").await.contains("Subject: Your JobLens password was changed"));
+
+        let _ = std::fs::remove_dir_all(&state.data_dir);
+    }
+}
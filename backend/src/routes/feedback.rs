@@ -0,0 +1,170 @@
+use actix_web::{web, HttpResponse};
+use chrono::Utc;
+use serde::Deserialize;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::models::{ApiResponse, FeedbackType, UserFeedback};
+use crate::state::{AppState, save_feedback};
+use crate::utils::auth::{forbidden, AuthUser};
+
+#[derive(Deserialize)]
+pub struct SubmitFeedbackPayload {
+    pub user_id: String,
+    pub job_id: String,
+    pub feedback_type: String,
+    pub rating: Option<i32>,
+    pub metadata: Option<serde_json::Value>,
+}
+
+#[derive(Deserialize)]
+pub struct FeedbackQuery {
+    pub user_id: Option<String>,
+    pub job_id: Option<String>,
+    pub feedback_type: Option<String>,
+}
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg
+        .route("/feedback", web::post().to(submit_feedback))
+        .route("/feedback", web::get().to(query_feedback))
+        .route("/feedback/summary", web::get().to(feedback_summary))
+        .route("/feedback/user/{user_id}", web::get().to(get_user_feedback))
+        .route("/feedback/job/{job_id}", web::get().to(get_job_feedback));
+}
+
+async fn submit_feedback(state: web::Data<AppState>, auth: AuthUser, payload: web::Json<SubmitFeedbackPayload>) -> HttpResponse {
+    let feedback_type = match FeedbackType::parse(&payload.feedback_type) {
+        Some(t) => t,
+        None => return HttpResponse::BadRequest().json(ApiResponse::<serde_json::Value> { success: false, data: None, message: format!("Unknown feedback_type: {}", payload.feedback_type) }),
+    };
+    let (min, max) = feedback_type.rating_range();
+    let rating = match payload.rating {
+        Some(r) if r >= min && r <= max => r,
+        _ => return HttpResponse::BadRequest().json(ApiResponse::<serde_json::Value> {
+            success: false,
+            data: None,
+            message: format!("rating for {} must be between {} and {}", feedback_type.as_str(), min, max),
+        }),
+    };
+    let job_id = payload.job_id.trim();
+    if job_id.is_empty() {
+        return HttpResponse::BadRequest().json(ApiResponse::<serde_json::Value> { success: false, data: None, message: "job_id is required".into() });
+    }
+    if !auth.can_access(&payload.user_id) { return forbidden(); }
+    if !state.users.lock().unwrap().iter().any(|u| u.id == payload.user_id) {
+        return HttpResponse::NotFound().json(ApiResponse::<serde_json::Value> { success: false, data: None, message: "User not found".into() });
+    }
+
+    let now = Utc::now().to_rfc3339();
+    let mut feedback = state.feedback.lock().unwrap();
+    // One opinion per user, job and type: re-rating replaces the earlier answer
+    let saved = if let Some(existing) = feedback.iter_mut().find(|f| f.user_id == payload.user_id && f.job_id == job_id && f.feedback_type == feedback_type) {
+        existing.rating = rating;
+        existing.timestamp = now;
+        if payload.metadata.is_some() { existing.metadata = payload.metadata.clone(); }
+        existing.clone()
+    } else {
+        let entry = UserFeedback {
+            id: Uuid::new_v4().to_string(),
+            user_id: payload.user_id.clone(),
+            job_id: job_id.to_string(),
+            feedback_type,
+            rating,
+            timestamp: now,
+            metadata: payload.metadata.clone(),
+        };
+        feedback.push(entry.clone());
+        entry
+    };
+    let _ = save_feedback(&feedback);
+
+    HttpResponse::Ok().json(ApiResponse { success: true, data: Some(saved), message: "Feedback recorded".into() })
+}
+
+fn filter_feedback(state: &AppState, user_id: Option<&str>, job_id: Option<&str>, feedback_type: Option<&str>) -> Result<Vec<UserFeedback>, HttpResponse> {
+    let type_filter = match feedback_type {
+        Some(t) => match FeedbackType::parse(t) {
+            Some(ft) => Some(ft),
+            None => return Err(HttpResponse::BadRequest().json(ApiResponse::<serde_json::Value> { success: false, data: None, message: format!("Unknown feedback_type: {}", t) })),
+        },
+        None => None,
+    };
+    let feedback = state.feedback.lock().unwrap();
+    let mut results: Vec<UserFeedback> = feedback.iter()
+        .filter(|f| user_id.map(|u| f.user_id == u).unwrap_or(true))
+        .filter(|f| job_id.map(|j| f.job_id == j).unwrap_or(true))
+        .filter(|f| type_filter.map(|t| f.feedback_type == t).unwrap_or(true))
+        .cloned()
+        .collect();
+    results.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+    Ok(results)
+}
+
+/// Whose feedback a listing covers: the requested user when the caller may see theirs,
+/// otherwise the caller's own.
+fn feedback_owner<'a>(auth: &'a AuthUser, requested: Option<&'a str>) -> Result<&'a str, HttpResponse> {
+    match requested {
+        Some(u) if !auth.can_access(u) => Err(forbidden()),
+        Some(u) => Ok(u),
+        None => Ok(&auth.user_id),
+    }
+}
+
+async fn query_feedback(state: web::Data<AppState>, auth: AuthUser, query: web::Query<FeedbackQuery>) -> HttpResponse {
+    let user_id = match feedback_owner(&auth, query.user_id.as_deref()) { Ok(u) => u, Err(resp) => return resp };
+    match filter_feedback(&state, Some(user_id), query.job_id.as_deref(), query.feedback_type.as_deref()) {
+        Ok(results) => HttpResponse::Ok().json(ApiResponse { success: true, data: Some(results), message: "Feedback fetched".into() }),
+        Err(resp) => resp,
+    }
+}
+
+async fn get_user_feedback(state: web::Data<AppState>, auth: AuthUser, path: web::Path<String>, query: web::Query<FeedbackQuery>) -> HttpResponse {
+    let user_id = path.into_inner();
+    if !auth.can_access(&user_id) { return forbidden(); }
+    match filter_feedback(&state, Some(&user_id), query.job_id.as_deref(), query.feedback_type.as_deref()) {
+        Ok(results) => HttpResponse::Ok().json(ApiResponse { success: true, data: Some(results), message: "Feedback fetched".into() }),
+        Err(resp) => resp,
+    }
+}
+
+async fn get_job_feedback(state: web::Data<AppState>, auth: AuthUser, path: web::Path<String>, query: web::Query<FeedbackQuery>) -> HttpResponse {
+    let job_id = path.into_inner();
+    let user_id = match feedback_owner(&auth, query.user_id.as_deref()) { Ok(u) => u, Err(resp) => return resp };
+    match filter_feedback(&state, Some(user_id), Some(&job_id), query.feedback_type.as_deref()) {
+        Ok(results) => HttpResponse::Ok().json(ApiResponse { success: true, data: Some(results), message: "Feedback fetched".into() }),
+        Err(resp) => resp,
+    }
+}
+
+/// Per-type rating statistics, used to judge how well recommendations and match
+/// scores line up with what users actually think of them.
+async fn feedback_summary(state: web::Data<AppState>, auth: AuthUser, query: web::Query<FeedbackQuery>) -> HttpResponse {
+    if !auth.is_admin() { return forbidden(); }
+    let results = match filter_feedback(&state, query.user_id.as_deref(), query.job_id.as_deref(), query.feedback_type.as_deref()) {
+        Ok(r) => r,
+        Err(resp) => return resp,
+    };
+
+    let mut summary: Vec<serde_json::Value> = vec![];
+    for t in FeedbackType::ALL.iter() {
+        let ratings: Vec<i32> = results.iter().filter(|f| f.feedback_type == *t).map(|f| f.rating).collect();
+        if ratings.is_empty() { continue; }
+        let (min, max) = t.rating_range();
+        let mut distribution: HashMap<i32, usize> = (min..=max).map(|r| (r, 0)).collect();
+        for r in ratings.iter() { *distribution.entry(*r).or_insert(0) += 1; }
+        let average = ratings.iter().sum::<i32>() as f64 / ratings.len() as f64;
+        // Top of the scale counts as positive (4-5 stars, or a successful outcome)
+        let positive_from = if max - min >= 2 { max - 1 } else { max };
+        let positive = ratings.iter().filter(|r| **r >= positive_from).count();
+        summary.push(serde_json::json!({
+            "feedback_type": t,
+            "count": ratings.len(),
+            "average_rating": (average * 100.0).round() / 100.0,
+            "positive_rate": positive as f64 / ratings.len() as f64,
+            "distribution": distribution,
+        }));
+    }
+
+    HttpResponse::Ok().json(ApiResponse { success: true, data: Some(summary), message: "Feedback summary".into() })
+}
@@ -0,0 +1,142 @@
+use actix_web::{web, HttpResponse};
+use chrono::Utc;
+use rand::Rng;
+use std::env;
+use std::time::{Duration, Instant};
+
+use crate::models::{ApiResponse, SyncReport};
+use crate::routes::jobs::run_sync;
+use crate::state::AppState;
+
+/// Worker settings, read from the environment:
+/// `JOB_SYNC_INTERVAL_SECS` (default 3600, 0 disables the worker),
+/// `JOB_SYNC_RETRY_SECS` (first retry after a failure, default 60),
+/// `JOB_SYNC_MAX_BACKOFF_SECS` (default 21600) and
+/// `JOB_SYNC_JITTER_PCT` (random +/- spread applied to every delay, default 10).
+#[derive(Debug, Clone)]
+pub struct IngestionConfig {
+    pub interval: Duration,
+    pub retry: Duration,
+    pub max_backoff: Duration,
+    pub jitter_pct: u64,
+}
+
+impl IngestionConfig {
+    pub fn from_env() -> Self {
+        let secs = |key: &str, default: u64| env::var(key).ok().and_then(|v| v.parse().ok()).unwrap_or(default);
+        IngestionConfig {
+            interval: Duration::from_secs(secs("JOB_SYNC_INTERVAL_SECS", 3600)),
+            retry: Duration::from_secs(secs("JOB_SYNC_RETRY_SECS", 60).max(1)),
+            max_backoff: Duration::from_secs(secs("JOB_SYNC_MAX_BACKOFF_SECS", 21600)),
+            jitter_pct: secs("JOB_SYNC_JITTER_PCT", 10).min(100),
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        !self.interval.is_zero()
+    }
+
+    /// Delay before the next run: the regular interval after a success, otherwise
+    /// `retry * 2^(failures - 1)` capped at `max_backoff` (and never above the interval).
+    fn next_delay(&self, consecutive_failures: u32) -> Duration {
+        let base = if consecutive_failures == 0 {
+            self.interval
+        } else {
+            let factor = 2u32.saturating_pow(consecutive_failures - 1);
+            self.retry.saturating_mul(factor).min(self.max_backoff).min(self.interval)
+        };
+        self.with_jitter(base)
+    }
+
+    fn with_jitter(&self, base: Duration) -> Duration {
+        let spread = base.as_millis() as u64 * self.jitter_pct / 100;
+        if spread == 0 { return base; }
+        let offset = rand::thread_rng().gen_range(0..=spread * 2);
+        Duration::from_millis((base.as_millis() as u64 + offset).saturating_sub(spread))
+    }
+}
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.route("/jobs/sync/status", web::get().to(sync_status));
+}
+
+/// Clears the `running` flag however the sync ends, including when its future is
+/// dropped (a manual sync whose client disconnected) or it panics.
+struct RunningGuard<'a>(&'a AppState);
+
+impl Drop for RunningGuard<'_> {
+    fn drop(&mut self) {
+        self.0.ingestion.lock().unwrap_or_else(|e| e.into_inner()).running = false;
+    }
+}
+
+/// Run one catalog sync and record its outcome in the ingestion status.
+/// Only one sync runs at a time; a second caller gets an error instead of queueing.
+pub async fn sync_and_record(state: &AppState, trigger: &str) -> Result<SyncReport, String> {
+    {
+        let mut status = state.ingestion.lock().unwrap();
+        if status.running {
+            return Err("A job sync is already running".into());
+        }
+        status.running = true;
+        status.last_trigger = Some(trigger.to_string());
+        status.last_started_at = Some(Utc::now().to_rfc3339());
+    }
+    let _running = RunningGuard(state);
+
+    let started = Instant::now();
+    let result = run_sync(state).await;
+
+    let mut status = state.ingestion.lock().unwrap();
+    status.running = false;
+    status.total_runs += 1;
+    status.last_finished_at = Some(Utc::now().to_rfc3339());
+    status.last_duration_ms = Some(started.elapsed().as_millis() as u64);
+    match &result {
+        Ok(report) => {
+            status.last_success_at = status.last_finished_at.clone();
+            status.last_report = Some(report.clone());
+            status.last_error = None;
+            status.consecutive_failures = 0;
+        }
+        Err(e) => {
+            status.last_error = Some(e.clone());
+            status.consecutive_failures += 1;
+            status.total_failures += 1;
+        }
+    }
+    result
+}
+
+/// Periodically refresh the catalog for the lifetime of the server.
+pub async fn run_worker(state: web::Data<AppState>, config: IngestionConfig) {
+    {
+        let mut status = state.ingestion.lock().unwrap();
+        status.worker_enabled = config.enabled();
+        status.interval_secs = config.interval.as_secs();
+    }
+    if !config.enabled() {
+        println!("ℹ️ Background job sync disabled (JOB_SYNC_INTERVAL_SECS=0)");
+        return;
+    }
+    println!("⏱️ Background job sync every {}s", config.interval.as_secs());
+
+    // Give the server a moment to come up, spread out so restarts don't all hit upstream at once
+    let mut delay = config.with_jitter(Duration::from_secs(5));
+    loop {
+        let next = Utc::now() + chrono::Duration::milliseconds(delay.as_millis() as i64);
+        state.ingestion.lock().unwrap().next_run_at = Some(next.to_rfc3339());
+        actix_web::rt::time::sleep(delay).await;
+
+        if let Err(e) = sync_and_record(&state, "scheduled").await {
+            println!("❌ Scheduled job sync failed: {}", e);
+        }
+        let failures = state.ingestion.lock().unwrap().consecutive_failures;
+        delay = config.next_delay(failures);
+    }
+}
+
+async fn sync_status(state: web::Data<AppState>) -> HttpResponse {
+    let status = state.ingestion.lock().unwrap().clone();
+    HttpResponse::Ok().json(ApiResponse { success: true, data: Some(status), message: "Job sync status".into() })
+}
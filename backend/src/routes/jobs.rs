@@ -2,13 +2,26 @@ use actix_web::{web, HttpResponse, Result};
 use serde::{Deserialize, Serialize};
 use reqwest::Client;
 use std::time::Duration;
-use uuid::Uuid;
-use chrono::{DateTime, Utc};
+use chrono::Utc;
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
 
-use crate::models::{ApiResponse, Job, JobsResponse};
+use crate::models::{ApiResponse, Job, JobsResponse, SearchFacets, SearchHighlights, SearchHit, SearchResponse, SyncReport};
 use crate::state::AppState;
-
-#[derive(Deserialize)]
+use crate::utils::catalog::merge_jobs;
+use crate::utils::auth::{forbidden, AuthUser};
+use crate::utils::dedup::dedupe_jobs;
+use crate::utils::facets::{region_counts, salary_counts, value_counts};
+use crate::utils::location::{eligibility, job_location, resolve_place, Eligibility, Place};
+use crate::utils::search::{highlight, snippet, SearchIndex};
+use crate::sources::{configured_sources, fetch};
+use crate::routes::alerts::evaluate_job_alerts;
+use crate::routes::ingestion::sync_and_record;
+use crate::routes::notifications::notify_high_match_jobs;
+use crate::utils::matching::COMMON_TECH_SKILLS;
+use crate::utils::text::strip_html;
+
+#[derive(Deserialize, Clone)]
 pub struct JobQuery {
     pub q: Option<String>,
     pub limit: Option<usize>,
@@ -16,28 +29,18 @@ pub struct JobQuery {
     pub category: Option<String>,
     pub company_name: Option<String>,
     pub search: Option<String>,
-}
-
-#[derive(Deserialize, Debug)]
-struct RemotiveJob {
-    id: i32,
-    url: String,
-    title: String,
-    company_name: String,
-    company_logo: Option<String>,
-    category: String,
-    job_type: Option<String>,
-    publication_date: String,
-    candidate_required_location: Option<String>,
-    salary: Option<String>,
-    description: String,
-}
-
-#[derive(Deserialize, Debug)]
-struct RemotiveResponse {
-    #[serde(rename = "job-count")]
-    job_count: i32,
-    jobs: Vec<RemotiveJob>,
+    pub job_type: Option<String>,
+    pub location: Option<String>,
+    pub sort: Option<String>,
+    pub include_expired: Option<bool>,
+    // Yearly amounts; hourly, daily, weekly and monthly pay is annualized before comparing
+    pub salary_min: Option<f64>,
+    pub salary_max: Option<f64>,
+    pub salary_currency: Option<String>,
+    // Only jobs open to someone in this region / country / this user's profile location
+    pub region: Option<String>,
+    pub country: Option<String>,
+    pub eligible_for: Option<String>,
 }
 
 #[derive(Serialize)]
@@ -73,110 +76,131 @@ pub fn config(cfg: &mut web::ServiceConfig) {
         .route("/jobs/detail-match/{user_id}", web::post().to(fetch_job_detail_and_match));
 }
 
-async fn get_jobs(state: web::Data<AppState>, query: web::Query<JobQuery>) -> Result<HttpResponse> {
-    println!("🔍 Fetching jobs from Remotive API...");
-
-    let client = Client::builder()
-        .timeout(Duration::from_secs(30))
-        .build()
-        .map_err(|_| actix_web::error::ErrorInternalServerError("Failed to create HTTP client"))?;
-
-    // Build Remotive API URL with query parameters
-    let mut url = "https://remotive.com/api/remote-jobs".to_string();
-    let mut params = Vec::new();
+/// "Full-Time", "full time" and "full_time" are one job type.
+fn normalize_job_type(job_type: &str) -> String {
+    job_type.trim().to_lowercase().replace(['-', ' '], "_")
+}
 
-    if let Some(limit) = query.limit {
-        params.push(format!("limit={}", limit.min(100))); // Cap at 100 for performance
-    } else {
-        params.push("limit=50".to_string()); // Default limit
+/// Case-insensitive filters over the local catalog, apart from the text query.
+fn job_matches(job: &Job, query: &JobQuery, places: &[Place]) -> bool {
+    if job.duplicate_of.is_some() { return false; }
+    if job.expired_at.is_some() && !query.include_expired.unwrap_or(false) { return false; }
+    if let Some(c) = &query.category {
+        if !job.category.as_deref().map(|jc| jc.eq_ignore_ascii_case(c.trim())).unwrap_or(false) { return false; }
     }
-
-    if let Some(category) = &query.category {
-        params.push(format!("category={}", urlencoding::encode(category)));
+    if let Some(c) = &query.company_name {
+        if !job.company.to_lowercase().contains(&c.trim().to_lowercase()) { return false; }
     }
-
-    if let Some(company_name) = &query.company_name {
-        params.push(format!("company_name={}", urlencoding::encode(company_name)));
+    if let Some(t) = &query.job_type {
+        if job.job_type.as_deref().map(normalize_job_type) != Some(normalize_job_type(t)) { return false; }
     }
-
-    if let Some(search) = &query.search {
-        params.push(format!("search={}", urlencoding::encode(search)));
-    } else if let Some(q) = &query.q {
-        params.push(format!("search={}", urlencoding::encode(q)));
+    if let Some(l) = &query.location {
+        if !job.location.as_deref().unwrap_or("").to_lowercase().contains(&l.trim().to_lowercase()) { return false; }
     }
-
-    if !params.is_empty() {
-        url.push('?');
-        url.push_str(&params.join("&"));
+    if !places.is_empty() {
+        let location = job_location(job);
+        if !places.iter().all(|p| eligibility(&location, p) == Eligibility::Eligible) { return false; }
+    }
+    if query.salary_min.is_some() || query.salary_max.is_some() || query.salary_currency.is_some() {
+        // Jobs without a parseable salary can't satisfy a pay filter
+        let range = match &job.salary_range { Some(r) => r, None => return false };
+        if !range.overlaps(query.salary_min, query.salary_max) { return false; }
+        if let Some(c) = &query.salary_currency {
+            if !range.currency.as_deref().map(|rc| rc.eq_ignore_ascii_case(c.trim())).unwrap_or(false) { return false; }
+        }
     }
+    true
+}
 
-    println!("📡 Requesting: {}", url);
+/// The text query: `q`, or `search` as older clients send it. Blank means none.
+fn query_text(query: &JobQuery) -> Option<&str> {
+    query.q.as_deref().or(query.search.as_deref()).map(str::trim).filter(|t| !t.is_empty())
+}
 
-    let response = client
-        .get(&url)
-        .header("User-Agent", "JobLens-Backend/1.0")
-        .send()
-        .await
-        .map_err(|e| {
-            println!("❌ Remotive API request failed: {}", e);
-            actix_web::error::ErrorInternalServerError("Failed to fetch jobs from Remotive")
-        })?;
-
-    if !response.status().is_success() {
-        println!("❌ Remotive API returned status: {}", response.status());
-        return Ok(HttpResponse::InternalServerError().json(ApiResponse::<JobsResponse> {
-            success: false,
-            data: None,
-            message: format!("Remotive API error: {}", response.status()),
-        }));
+fn sort_jobs(jobs: &mut [Job], sort: Option<&str>) -> Result<(), String> {
+    match sort.unwrap_or("newest") {
+        "newest" => jobs.sort_by(|a, b| b.created_at.cmp(&a.created_at).then_with(|| a.id.cmp(&b.id))),
+        "oldest" => jobs.sort_by(|a, b| a.created_at.cmp(&b.created_at).then_with(|| a.id.cmp(&b.id))),
+        "title" => jobs.sort_by(|a, b| a.title.to_lowercase().cmp(&b.title.to_lowercase()).then_with(|| a.id.cmp(&b.id))),
+        "company" => jobs.sort_by(|a, b| a.company.to_lowercase().cmp(&b.company.to_lowercase()).then_with(|| a.id.cmp(&b.id))),
+        // Best-paid first by the top of the range; jobs without a salary go last either way
+        "salary" => jobs.sort_by(|a, b| {
+            let pay = |j: &Job| j.salary_range.as_ref().and_then(|r| r.annual_max());
+            match (pay(a), pay(b)) {
+                (Some(x), Some(y)) => y.partial_cmp(&x).unwrap_or(Ordering::Equal),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => Ordering::Equal,
+            }.then_with(|| a.id.cmp(&b.id))
+        }),
+        "salary_asc" => jobs.sort_by(|a, b| {
+            let pay = |j: &Job| j.salary_range.as_ref().and_then(|r| r.annual_min());
+            match (pay(a), pay(b)) {
+                (Some(x), Some(y)) => x.partial_cmp(&y).unwrap_or(Ordering::Equal),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => Ordering::Equal,
+            }.then_with(|| a.id.cmp(&b.id))
+        }),
+        other => return Err(format!("Unknown sort: {} (expected newest, oldest, title, company, salary or salary_asc)", other)),
     }
+    Ok(())
+}
 
-    let remotive_response: RemotiveResponse = response
-        .json()
-        .await
-        .map_err(|e| {
-            println!("❌ Failed to parse Remotive response: {}", e);
-            actix_web::error::ErrorInternalServerError("Failed to parse jobs data")
-        })?;
-
-    println!("✅ Fetched {} jobs from Remotive", remotive_response.jobs.len());
-
-    // Convert Remotive jobs to our Job format
-    let mut jobs: Vec<Job> = Vec::new();
-    for remotive_job in remotive_response.jobs {
-        let job = Job {
-            id: Uuid::new_v4().to_string(), // Generate new UUID for our system
-            title: remotive_job.title,
-            company: remotive_job.company_name,
-            company_logo: remotive_job.company_logo,
-            location: remotive_job.candidate_required_location,
-            url: remotive_job.url,
-            description: remotive_job.description,
-            created_at: parse_remotive_date(&remotive_job.publication_date)
-                .unwrap_or_else(|| Utc::now().to_rfc3339()),
-            external_id: Some(remotive_job.id.to_string()),
-            job_type: remotive_job.job_type,
-            salary: remotive_job.salary,
-            category: Some(remotive_job.category),
-        };
-        jobs.push(job);
+/// The places named by `region`, `country` and `eligible_for`, or the error response to send.
+/// `eligible_for` reads a profile, so it needs a caller who may access that user.
+fn resolve_places(state: &AppState, auth: Option<&AuthUser>, query: &JobQuery) -> std::result::Result<Vec<Place>, HttpResponse> {
+    let mut places: Vec<Place> = vec![];
+    for (param, value) in [("region", &query.region), ("country", &query.country)] {
+        if let Some(v) = value {
+            match resolve_place(v) {
+                Some(p) => places.push(p),
+                None => return Err(HttpResponse::BadRequest().json(ApiResponse::<JobsResponse> { success: false, data: None, message: format!("Unknown {}: {}", param, v) })),
+            }
+        }
     }
-
-    // Apply additional filtering if needed
-    if let Some(q) = &query.q {
-        if query.search.is_none() {
-            let ql = q.to_lowercase();
-            jobs = jobs.into_iter().filter(|j|
-                j.title.to_lowercase().contains(&ql) ||
-                j.company.to_lowercase().contains(&ql) ||
-                j.description.to_lowercase().contains(&ql)
-            ).collect();
+    if let Some(user_id) = &query.eligible_for {
+        match auth {
+            None => return Err(HttpResponse::Unauthorized().json(ApiResponse::<JobsResponse> { success: false, data: None, message: "Sign in to filter by a profile location".into() })),
+            Some(a) if !a.can_access(user_id) => return Err(forbidden()),
+            Some(_) => {}
+        }
+        let user_location = state.users.lock().unwrap().iter().find(|u| &u.id == user_id).map(|u| u.location.clone());
+        match user_location.as_deref().map(|l| (l, resolve_place(l))) {
+            Some((_, Some(p))) => places.push(p),
+            Some((_, None)) => return Err(HttpResponse::BadRequest().json(ApiResponse::<JobsResponse> {
+                success: false,
+                data: None,
+                message: "The profile location is not recognized; set a country in the profile".into(),
+            })),
+            None => return Err(HttpResponse::NotFound().json(ApiResponse::<JobsResponse> { success: false, data: None, message: "User not found".into() })),
         }
     }
+    Ok(places)
+}
+
+/// List jobs from the synced catalog; Remotive is only contacted by `/jobs/sync`.
+async fn get_jobs(state: web::Data<AppState>, auth: Option<AuthUser>, query: web::Query<JobQuery>) -> Result<HttpResponse> {
+    let places = match resolve_places(&state, auth.as_ref(), &query) {
+        Ok(p) => p,
+        Err(resp) => return Ok(resp),
+    };
+    // Matched as /jobs/search matches, through the index
+    let matched: Option<HashSet<String>> = query_text(&query)
+        .map(|t| state.search_index.lock().unwrap().search(t).hits.into_iter().map(|(id, _)| id).collect());
+
+    let mut jobs: Vec<Job> = state.jobs_cache.lock().unwrap().iter()
+        .filter(|j| job_matches(j, &query, &places) && matched.as_ref().map(|m| m.contains(&j.id)).unwrap_or(true))
+        .cloned()
+        .collect();
+
+    if let Err(msg) = sort_jobs(&mut jobs, query.sort.as_deref()) {
+        return Ok(HttpResponse::BadRequest().json(ApiResponse::<JobsResponse> { success: false, data: None, message: msg }));
+    }
 
     let total = jobs.len();
     let offset = query.offset.unwrap_or(0);
-    let limit = query.limit.unwrap_or(50);
+    let limit = query.limit.unwrap_or(50).min(100); // Cap at 100 for performance
 
     // Apply pagination
     let paginated_jobs: Vec<Job> = jobs.into_iter().skip(offset).take(limit).collect();
@@ -191,121 +215,215 @@ async fn get_jobs(state: web::Data<AppState>, query: web::Query<JobQuery>) -> Re
     Ok(HttpResponse::Ok().json(ApiResponse {
         success: true,
         data: Some(response),
-        message: "Jobs fetched successfully from Remotive".to_string(),
+        message: "Jobs fetched successfully".to_string(),
     }))
 }
 
-async fn search_jobs(state: web::Data<AppState>, query: web::Query<JobQuery>) -> Result<HttpResponse> {
-    get_jobs(state, query).await
-}
-
-async fn sync_jobs(state: web::Data<AppState>) -> Result<HttpResponse> {
-    println!("🔄 Syncing jobs from Remotive API...");
-
-    let client = Client::builder()
-        .timeout(Duration::from_secs(60))
-        .build()
-        .map_err(|_| actix_web::error::ErrorInternalServerError("Failed to create HTTP client"))?;
+// Values listed per facet; the rest are dropped from the counts
+const FACET_LIMIT: usize = 20;
 
-    let url = "https://remotive.com/api/remote-jobs?limit=100";
+/// Counts for each facet over `candidates` (the jobs matching the text query),
+/// applying every filter except the facet's own.
+fn search_facets(state: &AppState, auth: Option<&AuthUser>, candidates: &[Job], query: &JobQuery, places: &[Place]) -> SearchFacets {
+    let matching = |q: &JobQuery, places: &[Place]| -> Vec<&Job> {
+        candidates.iter().filter(|j| job_matches(j, q, places)).collect()
+    };
+    let without = |clear: fn(&mut JobQuery)| { let mut q = query.clone(); clear(&mut q); q };
+
+    let category = matching(&without(|q| q.category = None), places);
+    let job_type = matching(&without(|q| q.job_type = None), places);
+    let company = matching(&without(|q| q.company_name = None), places);
+    let region_query = without(|q| q.region = None);
+    // Already validated by the caller, so the remaining places resolve
+    let region_places = resolve_places(state, auth, &region_query).unwrap_or_default();
+    let region = matching(&region_query, &region_places);
+    let salary = matching(&without(|q| { q.salary_min = None; q.salary_max = None; }), places);
+
+    SearchFacets {
+        category: value_counts(category.iter().filter_map(|j| j.category.as_deref()), FACET_LIMIT),
+        job_type: value_counts(job_type.iter().filter_map(|j| j.job_type.as_deref().map(normalize_job_type)), FACET_LIMIT),
+        company: value_counts(company.iter().map(|j| j.company.as_str()), FACET_LIMIT),
+        region: region_counts(&region),
+        salary: salary_counts(&salary),
+    }
+}
 
-    let response = client
-        .get(url)
-        .header("User-Agent", "JobLens-Backend/1.0")
-        .send()
-        .await
-        .map_err(|e| {
-            println!("❌ Sync request failed: {}", e);
-            actix_web::error::ErrorInternalServerError("Failed to sync jobs")
-        })?;
+/// Full-text search over the catalog index: results are ranked by BM25 relevance
+/// (`sort` may override the order) and carry highlighted title and snippet, plus
+/// facet counts for the filters. Without a query every job is a candidate, newest first.
+async fn search_jobs(state: web::Data<AppState>, auth: Option<AuthUser>, query: web::Query<JobQuery>) -> Result<HttpResponse> {
+    let places = match resolve_places(&state, auth.as_ref(), &query) {
+        Ok(p) => p,
+        Err(resp) => return Ok(resp),
+    };
+    let text = query_text(&query);
+
+    let (candidates, scores, matched_terms) = match text {
+        Some(t) => {
+            let results = state.search_index.lock().unwrap().search(t);
+            let cache = state.jobs_cache.lock().unwrap();
+            let by_id: HashMap<&str, &Job> = cache.iter().map(|j| (j.id.as_str(), j)).collect();
+            let candidates: Vec<Job> = results.hits.iter().filter_map(|(id, _)| by_id.get(id.as_str()).map(|j| (*j).clone())).collect();
+            (candidates, results.hits.into_iter().collect::<HashMap<String, f64>>(), results.matched_terms)
+        }
+        None => (state.jobs_cache.lock().unwrap().clone(), HashMap::new(), HashSet::new()),
+    };
 
-    if !response.status().is_success() {
-        return Ok(HttpResponse::InternalServerError().json(ApiResponse::<serde_json::Value> {
-            success: false,
-            data: None,
-            message: format!("Sync failed with status: {}", response.status()),
-        }));
+    let mut jobs: Vec<Job> = candidates.iter().filter(|j| job_matches(j, &query, &places)).cloned().collect();
+    let sort = query.sort.as_deref().or(if text.is_none() { Some("newest") } else { None });
+    if let Some(sort) = sort.filter(|s| *s != "relevance") {
+        if let Err(msg) = sort_jobs(&mut jobs, Some(sort)) {
+            return Ok(HttpResponse::BadRequest().json(ApiResponse::<SearchResponse> { success: false, data: None, message: msg }));
+        }
     }
+    let facets = search_facets(&state, auth.as_ref(), &candidates, &query, &places);
 
-    let remotive_response: RemotiveResponse = response
-        .json()
-        .await
-        .map_err(|_| actix_web::error::ErrorInternalServerError("Failed to parse sync response"))?;
-
-    println!("✅ Synced {} jobs from Remotive", remotive_response.jobs.len());
+    let total = jobs.len();
+    let offset = query.offset.unwrap_or(0);
+    let limit = query.limit.unwrap_or(50).min(100);
+    let hits: Vec<SearchHit> = jobs.into_iter().skip(offset).take(limit)
+        .map(|job| SearchHit {
+            score: scores.get(&job.id).copied().unwrap_or(0.0),
+            highlights: SearchHighlights {
+                title: highlight(&job.title, &matched_terms),
+                snippet: snippet(&strip_html(&job.description), &matched_terms),
+            },
+            job,
+        })
+        .collect();
 
     Ok(HttpResponse::Ok().json(ApiResponse {
         success: true,
-        data: Some(serde_json::json!({
-            "synced_count": remotive_response.jobs.len(),
-            "source": "remotive"
-        })),
-        message: format!("Successfully synced {} jobs from Remotive", remotive_response.jobs.len()),
+        data: Some(SearchResponse { jobs: hits, total, limit, offset, facets }),
+        message: match text {
+            Some(t) => format!("Found {} jobs matching '{}'", total, t),
+            None => format!("Found {} jobs", total),
+        },
     }))
 }
 
-async fn get_job_by_id(path: web::Path<String>) -> Result<HttpResponse> {
-    let job_id = path.into_inner();
+/// Fetch every configured job source and merge each listing into the jobs cache:
+/// new postings are added, changed ones updated in place, missing ones marked
+/// expired. A failing source is reported and leaves its jobs untouched; the run
+/// only fails when no source could be fetched.
+pub async fn run_sync(state: &AppState) -> Result<SyncReport, String> {
+    let sources = configured_sources()?;
+    println!("🔄 Syncing jobs from {} source(s)...", sources.len());
+
+    let now = Utc::now().to_rfc3339();
+    let mut report = SyncReport { synced_at: now.clone(), ..Default::default() };
+    let mut added: Vec<Job> = vec![];
+    // Only jobs that differ from this snapshot are written back
+    let before: HashMap<String, Job> = state.jobs_cache.lock().unwrap().iter().map(|j| (j.id.clone(), j.clone())).collect();
+
+    for source in sources.iter() {
+        let fetched = match fetch(source.as_ref()).await {
+            Ok(jobs) => jobs,
+            Err(e) => {
+                println!("❌ Sync of {} failed: {}", source.name(), e);
+                report.failed_sources.push(format!("{}: {}", source.name(), e));
+                continue;
+            }
+        };
+        let synced_count = fetched.len();
+        let outcome = merge_jobs(&mut state.jobs_cache.lock().unwrap(), source.name(), fetched, &now);
+        println!("✅ Synced {} jobs from {}: {} added, {} updated, {} expired", synced_count, source.name(), outcome.added.len(), outcome.updated, outcome.expired);
+
+        report.sources.push(source.name().to_string());
+        report.synced_count += synced_count;
+        report.jobs_added += outcome.added.len();
+        report.jobs_updated += outcome.updated;
+        report.jobs_expired += outcome.expired;
+        added.extend(outcome.added);
+    }
 
-    // Try to fetch job details from Remotive API using the external ID
-    let client = Client::builder()
-        .timeout(Duration::from_secs(30))
-        .build()
-        .map_err(|_| actix_web::error::ErrorInternalServerError("Failed to create HTTP client"))?;
+    if report.sources.is_empty() && !report.failed_sources.is_empty() {
+        return Err(format!("Failed to sync jobs: {}", report.failed_sources.join("; ")));
+    }
+    let (changed, saved) = {
+        let mut cache = state.jobs_cache.lock().unwrap();
+        // The same posting may arrive through several sources; only the canonical copy is listed
+        report.duplicates = dedupe_jobs(&mut cache);
+        *state.search_index.lock().unwrap() = SearchIndex::build(&cache);
+        let mut changed: HashSet<String> = cache.iter().filter(|j| before.get(&j.id) != Some(*j)).map(|j| j.id.clone()).collect();
+        changed.extend(state.unsaved_jobs.lock().unwrap().drain());
+        let listed: HashSet<&str> = cache.iter().filter(|j| j.is_listed()).map(|j| j.id.as_str()).collect();
+        added.retain(|j| listed.contains(j.id.as_str()));
+        report.total_active = listed.len();
+        let saved = state.storage.save_jobs(&cache, &changed);
+        (changed, saved)
+    };
+    let persisted = saved.await;
 
-    // Since Remotive doesn't provide individual job endpoints, we'll search for it
-    let url = format!("https://remotive.com/api/remote-jobs?limit=100");
+    // Only postings we have never seen before are announced. They are listed from memory
+    // even when saving failed, and won't count as new on the next sync.
+    notify_high_match_jobs(state, &added);
+    evaluate_job_alerts(state, &added);
 
-    let response = client
-        .get(&url)
-        .header("User-Agent", "JobLens-Backend/1.0")
-        .send()
-        .await
-        .map_err(|_| actix_web::error::ErrorInternalServerError("Failed to fetch job details"))?;
+    match persisted {
+        Ok(()) => Ok(report),
+        Err(e) => {
+            state.unsaved_jobs.lock().unwrap().extend(changed);
+            Err(format!("Synced jobs but failed to save the catalog: {}", e))
+        }
+    }
+}
 
-    if !response.status().is_success() {
-        return Ok(HttpResponse::NotFound().json(ApiResponse::<Job> {
+async fn sync_jobs(state: web::Data<AppState>, auth: AuthUser) -> Result<HttpResponse> {
+    if !auth.is_admin() { return Ok(forbidden()); }
+    match sync_and_record(&state, "manual").await {
+        Ok(report) => Ok(HttpResponse::Ok().json(ApiResponse {
+            success: true,
+            message: format!(
+                "Successfully synced {} jobs from {} ({} added, {} updated, {} expired)",
+                report.synced_count, report.sources.join(", "), report.jobs_added, report.jobs_updated, report.jobs_expired
+            ),
+            data: Some(report),
+        })),
+        Err(msg) => Ok(HttpResponse::InternalServerError().json(ApiResponse::<SyncReport> {
             success: false,
             data: None,
-            message: "Job not found".to_string(),
-        }));
+            message: msg,
+        })),
     }
+}
 
-    let remotive_response: RemotiveResponse = response
-        .json()
-        .await
-        .map_err(|_| actix_web::error::ErrorInternalServerError("Failed to parse job data"))?;
-
-    // Find the job by ID
-    if let Some(remotive_job) = remotive_response.jobs.into_iter().find(|j| j.id.to_string() == job_id) {
-        let job = Job {
-            id: Uuid::new_v4().to_string(),
-            title: remotive_job.title,
-            company: remotive_job.company_name,
-            company_logo: remotive_job.company_logo,
-            location: remotive_job.candidate_required_location,
-            url: remotive_job.url,
-            description: remotive_job.description,
-            created_at: parse_remotive_date(&remotive_job.publication_date)
-                .unwrap_or_else(|| Utc::now().to_rfc3339()),
-            external_id: Some(remotive_job.id.to_string()),
-            job_type: remotive_job.job_type,
-            salary: remotive_job.salary,
-            category: Some(remotive_job.category),
-        };
+async fn get_job_by_id(state: web::Data<AppState>, path: web::Path<String>) -> Result<HttpResponse> {
+    let job_id = path.into_inner();
 
-        Ok(HttpResponse::Ok().json(ApiResponse {
-            success: true,
-            data: Some(job),
-            message: "Job found".to_string(),
-        }))
-    } else {
-        Ok(HttpResponse::NotFound().json(ApiResponse::<Job> {
+    // Look the job up by our stable id or the source's external id; expired jobs stay reachable
+    let cache = state.jobs_cache.lock().unwrap();
+    let job = match cache.iter().find(|j| j.id == job_id || j.external_id.as_deref() == Some(job_id.as_str())) {
+        Some(j) => j,
+        None => return Ok(HttpResponse::NotFound().json(ApiResponse::<Job> {
             success: false,
             data: None,
             message: format!("Job with id {} not found", job_id),
+        })),
+    };
+
+    // Other copies of the same posting, whether this job is the canonical one or a duplicate
+    let canonical = job.duplicate_of.as_deref().unwrap_or(&job.id);
+    let duplicates: Vec<serde_json::Value> = cache.iter()
+        .filter(|j| j.id != job.id && (j.id == canonical || j.duplicate_of.as_deref() == Some(canonical)))
+        .map(|j| serde_json::json!({
+            "id": j.id,
+            "source": j.source_name(),
+            "external_id": j.external_id,
+            "url": j.url,
+            "title": j.title,
+            "company": j.company,
+            "canonical": j.id == canonical,
         }))
-    }
+        .collect();
+
+    let mut data = serde_json::to_value(job).unwrap_or_default();
+    data["duplicates"] = serde_json::Value::Array(duplicates);
+    Ok(HttpResponse::Ok().json(ApiResponse {
+        success: true,
+        data: Some(data),
+        message: "Job found".to_string(),
+    }))
 }
 
 #[derive(Deserialize)]
@@ -316,81 +434,33 @@ struct JobDetailMatchRequest {
 // New endpoint to fetch job details from URL and match against user CV
 async fn fetch_job_detail_and_match(
     state: web::Data<AppState>,
+    auth: AuthUser,
     path: web::Path<String>,
     req: web::Json<JobDetailMatchRequest>
 ) -> Result<HttpResponse> {
     let user_id = path.into_inner();
+    if !auth.can_access(&user_id) { return Ok(forbidden()); }
     let job_url = &req.job_url;
 
-    println!("🔍 Fetching job details from URL: {}", job_url);
+    println!("🔍 Looking up job by URL: {}", job_url);
 
     // Extract job ID from Remotive URL if possible
-    let job_id = extract_job_id_from_url(job_url);
-
-    let client = Client::builder()
-        .timeout(Duration::from_secs(30))
-        .build()
-        .map_err(|_| actix_web::error::ErrorInternalServerError("Failed to create HTTP client"))?;
-
-    // Fetch jobs from Remotive API to find the matching job
-    let api_url = "https://remotive.com/api/remote-jobs?limit=100";
-
-    let response = client
-        .get(api_url)
-        .header("User-Agent", "JobLens-Backend/1.0")
-        .send()
-        .await
-        .map_err(|e| {
-            println!("❌ Failed to fetch jobs: {}", e);
-            actix_web::error::ErrorInternalServerError("Failed to fetch job details")
-        })?;
-
-    if !response.status().is_success() {
-        return Ok(HttpResponse::InternalServerError().json(ApiResponse::<serde_json::Value> {
-            success: false,
-            data: None,
-            message: "Failed to fetch job details from Remotive".to_string(),
-        }));
-    }
-
-    let remotive_response: RemotiveResponse = response
-        .json()
-        .await
-        .map_err(|e| {
-            println!("❌ Failed to parse response: {}", e);
-            actix_web::error::ErrorInternalServerError("Failed to parse job data")
-        })?;
-
-    // Find the job that matches the URL or ID
-    let matching_job = if let Some(job_id) = job_id {
-        remotive_response.jobs.into_iter().find(|j| j.id == job_id)
-    } else {
-        // If we can't extract ID, try to find by URL match
-        remotive_response.jobs.into_iter().find(|j| j.url == *job_url)
+    let job_id = extract_job_id_from_url(job_url).map(|id| id.to_string());
+
+    // Find the job in the catalog that matches the URL or ID
+    let matching_job = {
+        let cache = state.jobs_cache.lock().unwrap();
+        cache.iter()
+            .find(|j| j.url == *job_url)
+            .or_else(|| job_id.as_deref().and_then(|id| cache.iter().find(|j| j.source_name() == "remotive" && j.external_id.as_deref() == Some(id))))
+            .cloned()
     };
 
-    if let Some(remotive_job) = matching_job {
-        println!("✅ Found job: {}", remotive_job.title);
-
-        // Convert to our Job format
-        let job = Job {
-            id: Uuid::new_v4().to_string(),
-            title: remotive_job.title.clone(),
-            company: remotive_job.company_name.clone(),
-            company_logo: remotive_job.company_logo.clone(),
-            location: remotive_job.candidate_required_location.clone(),
-            url: remotive_job.url.clone(),
-            description: remotive_job.description.clone(),
-            created_at: parse_remotive_date(&remotive_job.publication_date)
-                .unwrap_or_else(|| Utc::now().to_rfc3339()),
-            external_id: Some(remotive_job.id.to_string()),
-            job_type: remotive_job.job_type.clone(),
-            salary: remotive_job.salary.clone(),
-            category: Some(remotive_job.category.clone()),
-        };
+    if let Some(job) = matching_job {
+        println!("✅ Found job: {}", job.title);
 
         // Perform CV matching analysis using ML service with real user data
-        let match_result = analyze_job_cv_match(&user_id, &remotive_job, &state).await?;
+        let match_result = analyze_job_cv_match(&user_id, &job, &state).await?;
 
         Ok(HttpResponse::Ok().json(ApiResponse {
             success: true,
@@ -404,7 +474,7 @@ async fn fetch_job_detail_and_match(
         Ok(HttpResponse::NotFound().json(ApiResponse::<serde_json::Value> {
             success: false,
             data: None,
-            message: "Job not found in Remotive database".to_string(),
+            message: "Job not found in the job catalog".to_string(),
         }))
     }
 }
@@ -413,8 +483,8 @@ async fn fetch_job_detail_and_match(
 fn extract_job_id_from_url(url: &str) -> Option<i32> {
     // Remotive URLs typically look like: https://remotive.com/remote-jobs/category/job-title-123
     // Extract the number at the end
-    if let Some(last_part) = url.split('/').last() {
-        if let Some(id_part) = last_part.split('-').last() {
+    if let Some(last_part) = url.split('/').next_back() {
+        if let Some(id_part) = last_part.split('-').next_back() {
             if let Ok(id) = id_part.parse::<i32>() {
                 return Some(id);
             }
@@ -426,30 +496,32 @@ fn extract_job_id_from_url(url: &str) -> Option<i32> {
 // Analyze job against user's CV using the ML service
 async fn analyze_job_cv_match(
     user_id: &str,
-    job: &RemotiveJob,
+    job: &Job,
     state: &web::Data<AppState>
 ) -> Result<serde_json::Value> {
     println!("🧠 Analyzing job match for user: {} using ML service", user_id);
 
     // Gather user profile data
-    let (user_skills, user_experience, resume_text_opt) = {
+    let (user_skills, user_experience, resume_text_opt, user_location) = {
         let users = state.users.lock().unwrap();
         if let Some(u) = users.iter().find(|u| u.id == user_id) {
             let skills = u.skills.clone();
             let exp = u.experience_years as f64;
             // We don't parse PDFs here; provide empty resume_text and let ML use skills
-            (skills, exp, None::<String>)
+            (skills, exp, None::<String>, u.location.clone())
         } else {
-            (Vec::<String>::new(), 0.0_f64, None::<String>)
+            (Vec::<String>::new(), 0.0_f64, None::<String>, String::new())
         }
     };
+    let location_match = analyze_location_match(job, &user_location);
 
     // If we have no usable user data, fall back immediately
     if user_skills.is_empty() && resume_text_opt.as_deref().unwrap_or("").is_empty() {
         println!("ℹ️ User has no skills/resume; using fallback analysis");
+        state.metrics.lock().unwrap().job_fallback_analyses += 1;
         // Clean description before fallback analysis
         let cleaned_desc = strip_html(&job.description);
-        let fallback_analysis = create_fallback_analysis(&cleaned_desc, &job.title);
+        let fallback_analysis = create_fallback_analysis(&cleaned_desc, &job.title, location_match);
         return Ok(fallback_analysis);
     }
 
@@ -506,9 +578,10 @@ async fn analyze_job_cv_match(
                                     },
                                     "experience": {
                                         "score": data.get("experience_match").unwrap_or(&serde_json::Value::Number(serde_json::Number::from_f64(0.5).unwrap())),
-                                        "explanation": format!("Experience level matches job requirements"),
+                                        "explanation": "Experience level matches job requirements",
                                         "details": vec!["Based on job description analysis"]
-                                    }
+                                    },
+                                    "location": location_match
                                 },
                                 "recommendations": data.get("recommendations").unwrap_or(&serde_json::Value::String("Continue developing relevant skills".to_string())),
                                 "job_requirements": data.get("required_skills").unwrap_or(&serde_json::Value::Array(vec![])),
@@ -516,11 +589,12 @@ async fn analyze_job_cv_match(
                                 "match_explanation": format!(
                                     "Based on ML analysis of job requirements for {} at {}, this position has a {}% compatibility match.",
                                     job.title,
-                                    job.company_name,
+                                    job.company,
                                     data.get("match_score").unwrap_or(&serde_json::Value::Number(serde_json::Number::from_f64(50.0).unwrap())).as_f64().unwrap_or(50.0) as i32
                                 ),
                                 "service_status": "ML service active"
                             });
+                            state.metrics.lock().unwrap().job_ml_analyses += 1;
                             return Ok(analysis);
                         }
                     }
@@ -539,28 +613,24 @@ async fn analyze_job_cv_match(
 
     // Fallback to simple analysis if ML service fails
     println!("⚠️ ML service unavailable, using fallback analysis");
+    state.metrics.lock().unwrap().job_fallback_analyses += 1;
     let cleaned_desc = strip_html(&job.description);
-    let fallback_analysis = create_fallback_analysis(&cleaned_desc, &job.title);
+    let fallback_analysis = create_fallback_analysis(&cleaned_desc, &job.title, location_match);
     Ok(fallback_analysis)
 }
 
-fn create_fallback_analysis(description: &str, title: &str) -> serde_json::Value {
+fn create_fallback_analysis(description: &str, title: &str, location_match: MatchScore) -> serde_json::Value {
     let skills_match = analyze_skills_match(description, title);
     let experience_match = analyze_experience_match(description);
-    let location_match = analyze_location_match(&None); // No location for fallback
 
-    let overall_score = (skills_match.score + experience_match.score + 0.8) / 3.0; // 0.8 as location placeholder
+    let overall_score = (skills_match.score + experience_match.score + location_match.score) / 3.0;
 
     serde_json::json!({
         "overall_score": overall_score,
         "breakdown": {
             "skills": skills_match,
             "experience": experience_match,
-            "location": {
-                "score": 0.8,
-                "explanation": "Location compatibility assumed",
-                "details": ["Remote work supported"]
-            }
+            "location": location_match
         },
         "recommendations": generate_recommendations(overall_score, title),
         "job_requirements": extract_job_requirements(description),
@@ -587,11 +657,7 @@ fn analyze_skills_match(description: &str, title: &str) -> MatchScore {
     let mut score: f32 = 0.5; // Base score
 
     // Common tech skills
-    let tech_skills = vec![
-        "rust", "python", "javascript", "typescript", "react", "node.js", "docker",
-        "kubernetes", "aws", "gcp", "sql", "mongodb", "redis", "git", "linux",
-        "java", "c++", "go", "html", "css", "angular", "vue", "express"
-    ];
+    let tech_skills = COMMON_TECH_SKILLS;
 
     for skill in tech_skills {
         if desc_lower.contains(skill) || title_lower.contains(skill) {
@@ -644,22 +710,36 @@ fn analyze_experience_match(description: &str) -> MatchScore {
     }
 }
 
-fn analyze_location_match(location: &Option<String>) -> MatchScore {
-    match location {
-        Some(loc) if loc.to_lowercase().contains("remote") => MatchScore {
+/// Score whether the user may apply from where they live: 1.0 when eligible, 0.1 when
+/// the posting excludes their location, 0.6 when either side is too vague to tell.
+fn analyze_location_match(job: &Job, user_location: &str) -> MatchScore {
+    let job_loc = job.location.clone().unwrap_or_else(|| "Not specified".to_string());
+    let place = match resolve_place(user_location) {
+        Some(p) => p,
+        None => {
+            let worldwide = job_location(job).worldwide;
+            return MatchScore {
+                score: if worldwide { 1.0 } else { 0.6 },
+                explanation: if worldwide { "Open worldwide".to_string() } else { "Add your country to your profile to check location eligibility".to_string() },
+                details: vec![format!("Job location: {}", job_loc)],
+            };
+        }
+    };
+    match eligibility(&job_location(job), &place) {
+        Eligibility::Eligible => MatchScore {
             score: 1.0,
-            explanation: "Remote position - perfect location match".to_string(),
-            details: vec!["Remote work opportunity".to_string()],
+            explanation: format!("You can apply from {}", place.label),
+            details: vec![format!("Job location: {}", job_loc)],
         },
-        Some(loc) => MatchScore {
-            score: 0.7,
-            explanation: format!("Location: {}", loc),
-            details: vec![format!("Based in {}", loc)],
+        Eligibility::Ineligible => MatchScore {
+            score: 0.1,
+            explanation: format!("Restricted to {}, which does not include {}", job_loc, place.label),
+            details: vec![format!("Job location: {}", job_loc)],
         },
-        None => MatchScore {
-            score: 0.8,
-            explanation: "Location not specified".to_string(),
-            details: vec!["Location requirements unclear".to_string()],
+        Eligibility::Unknown => MatchScore {
+            score: 0.6,
+            explanation: "Location requirements unclear".to_string(),
+            details: vec![format!("Job location: {}", job_loc)],
         },
     }
 }
@@ -708,120 +788,33 @@ fn extract_job_requirements(description: &str) -> Vec<String> {
     requirements
 }
 
-async fn match_job_with_user(state: web::Data<AppState>, path: web::Path<(String, String)>) -> Result<HttpResponse> {
+async fn match_job_with_user(state: web::Data<AppState>, auth: AuthUser, path: web::Path<(String, String)>) -> Result<HttpResponse> {
     let (job_id, user_id) = path.into_inner();
+    if !auth.can_access(&user_id) { return Ok(forbidden()); }
 
     println!("🔍 Matching job {} with user {}", job_id, user_id);
 
-    // Fetch the specific job from Remotive API
-    let client = Client::builder()
-        .timeout(Duration::from_secs(30))
-        .build()
-        .map_err(|_| actix_web::error::ErrorInternalServerError("Failed to create HTTP client"))?;
-
-    let url = "https://remotive.com/api/remote-jobs?limit=100";
-
-    let response = client
-        .get(url)
-        .header("User-Agent", "JobLens-Backend/1.0")
-        .send()
-        .await;
-
-    // Helper to fabricate a sample job and analyze
-    async fn analyze_with_sample(user_id: String, state: web::Data<AppState>, ext_id: String) -> Result<HttpResponse> {
-        let sample = RemotiveJob {
-            id: ext_id.parse::<i32>().unwrap_or(0),
-            url: "https://example.com/sample-job".to_string(),
-            title: "Software Engineer (Python/React)".to_string(),
-            company_name: "SampleCorp".to_string(),
-            company_logo: None,
-            category: "Software Development".to_string(),
-            job_type: Some("Full-time".to_string()),
-            publication_date: Utc::now().format("%Y-%m-%dT%H:%M:%S").to_string(),
-            candidate_required_location: Some("Remote".to_string()),
-            salary: Some("$80k-$120k".to_string()),
-            description: "We are seeking a Software Engineer with strong skills in Python, React, JavaScript, Docker, and AWS. Responsibilities include building scalable web applications, collaborating with cross-functional teams, and deploying services to cloud environments.".to_string(),
-        };
-        let analysis = analyze_job_cv_match(&user_id, &sample, &state).await?;
-        let job = serde_json::json!({
-            "id": Uuid::new_v4().to_string(),
-            "title": sample.title,
-            "company": sample.company_name,
-            "company_logo": sample.company_logo,
-            "location": sample.candidate_required_location,
-            "url": sample.url,
-            "description": sample.description,
-            "created_at": Utc::now().to_rfc3339(),
-            "external_id": sample.id.to_string(),
-            "job_type": sample.job_type,
-            "salary": sample.salary,
-            "category": sample.category,
-        });
-        Ok(HttpResponse::Ok().json(ApiResponse { success: true, data: Some(serde_json::json!({ "job": job, "match_analysis": analysis })), message: "Job matching completed (offline fallback)".to_string() }))
-    }
-
-    match response {
-        Ok(resp) => {
-            if !resp.status().is_success() {
-                println!("❌ Remotive fetch status: {}. Using sample fallback.", resp.status());
-                return analyze_with_sample(user_id, state, job_id).await;
-            }
-            let remotive_response: RemotiveResponse = resp
-                .json()
-                .await
-                .map_err(|_| actix_web::error::ErrorInternalServerError("Failed to parse job data"))?;
-
-            // Find the job by external ID
-            if let Some(remotive_job) = remotive_response.jobs.into_iter().find(|j| j.id.to_string() == job_id) {
-                // Perform CV matching analysis using ML service with real user data
-                let match_analysis = analyze_job_cv_match(&user_id, &remotive_job, &state).await?;
-
-                Ok(HttpResponse::Ok().json(ApiResponse {
-                    success: true,
-                    data: Some(serde_json::json!({
-                        "job": {
-                            "id": Uuid::new_v4().to_string(),
-                            "title": remotive_job.title,
-                            "company": remotive_job.company_name,
-                            "company_logo": remotive_job.company_logo,
-                            "location": remotive_job.candidate_required_location,
-                            "url": remotive_job.url,
-                            "description": remotive_job.description,
-                            "created_at": parse_remotive_date(&remotive_job.publication_date).unwrap_or_else(|| Utc::now().to_rfc3339()),
-                            "external_id": remotive_job.id.to_string(),
-                            "job_type": remotive_job.job_type,
-                            "salary": remotive_job.salary,
-                            "category": remotive_job.category,
-                        },
-                        "match_analysis": match_analysis
-                    })),
-                    message: "Job matching completed successfully".to_string(),
-                }))
-            } else {
-                println!("ℹ️ Job id {} not found in Remotive list. Using sample fallback.", job_id);
-                analyze_with_sample(user_id, state, job_id).await
-            }
-        }
-        Err(e) => {
-            println!("❌ Remotive fetch failed: {}. Using sample fallback.", e);
-            analyze_with_sample(user_id, state, job_id).await
-        }
-    }
-}
+    let found = state.jobs_cache.lock().unwrap().iter()
+        .find(|j| j.id == job_id || j.external_id.as_deref() == Some(job_id.as_str()))
+        .cloned();
+    let job = match found {
+        Some(j) => j,
+        None => return Ok(HttpResponse::NotFound().json(ApiResponse::<serde_json::Value> {
+            success: false,
+            data: None,
+            message: format!("Job with id {} not found", job_id),
+        })),
+    };
 
-fn parse_remotive_date(date_str: &str) -> Option<String> {
-    // Try to parse the Remotive date format: "2020-02-15T10:23:26"
-    if let Ok(parsed) = DateTime::parse_from_str(date_str, "%Y-%m-%dT%H:%M:%S") {
-        Some(parsed.with_timezone(&Utc).to_rfc3339())
-    } else if let Ok(parsed) = chrono::NaiveDateTime::parse_from_str(date_str, "%Y-%m-%dT%H:%M:%S") {
-        Some(parsed.and_utc().to_rfc3339())
-    } else {
-        None
-    }
-}
+    // Perform CV matching analysis using ML service with real user data
+    let match_analysis = analyze_job_cv_match(&user_id, &job, &state).await?;
 
-// Helper function to strip HTML tags
-fn strip_html(input: &str) -> String {
-    let re = regex::Regex::new(r"<[^>]+>").unwrap();
-    re.replace_all(input, " ").to_string()
+    Ok(HttpResponse::Ok().json(ApiResponse {
+        success: true,
+        data: Some(serde_json::json!({
+            "job": job,
+            "match_analysis": match_analysis
+        })),
+        message: "Job matching completed successfully".to_string(),
+    }))
 }
@@ -0,0 +1,45 @@
+use actix_web::{web, HttpResponse};
+use reqwest::Client;
+use std::time::Duration;
+
+use crate::models::ApiResponse;
+use crate::utils::auth::{forbidden, AuthUser};
+
+// Training runs inside the request on the ML service, so allow it a while
+const RETRAIN_TIMEOUT_SECS: u64 = 600;
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.route("/ml/retrain", web::post().to(retrain_model));
+}
+
+/// Admin only: ask the ML service to retrain its match model and relay the outcome.
+async fn retrain_model(auth: AuthUser) -> HttpResponse {
+    if !auth.is_admin() { return forbidden(); }
+    let ml_service_url = std::env::var("ML_SERVICE_URL")
+        .unwrap_or_else(|_| "http://localhost:5000".to_string());
+    let retrain_url = format!("{}/retrain", ml_service_url);
+    println!("🧠 Model retraining requested by {}, calling {}", auth.user_id, retrain_url);
+
+    let client = match Client::builder().timeout(Duration::from_secs(RETRAIN_TIMEOUT_SECS)).build() {
+        Ok(c) => c,
+        Err(_) => return HttpResponse::InternalServerError().json(ApiResponse::<serde_json::Value> { success: false, data: None, message: "Failed to create HTTP client".into() }),
+    };
+    match client.post(&retrain_url).send().await {
+        Ok(response) => {
+            let ok = response.status().is_success();
+            let body = response.json::<serde_json::Value>().await.unwrap_or(serde_json::Value::Null);
+            if ok {
+                println!("✅ Model retrained");
+                HttpResponse::Ok().json(ApiResponse { success: true, data: Some(body), message: "Model retrained".into() })
+            } else {
+                let reason = body.get("error").and_then(|e| e.as_str()).unwrap_or("ML service returned an error").to_string();
+                println!("❌ Model retraining failed: {}", reason);
+                HttpResponse::BadGateway().json(ApiResponse::<serde_json::Value> { success: false, data: None, message: reason })
+            }
+        }
+        Err(e) => {
+            println!("❌ Failed to call ML service: {}", e);
+            HttpResponse::BadGateway().json(ApiResponse::<serde_json::Value> { success: false, data: None, message: "ML service unavailable".into() })
+        }
+    }
+}
@@ -3,3 +3,10 @@ pub mod auth;
 pub mod users;
 pub mod jobs;
 pub mod resume;
+pub mod applications;
+pub mod feedback;
+pub mod analytics;
+pub mod notifications;
+pub mod alerts;
+pub mod ingestion;
+pub mod ml;
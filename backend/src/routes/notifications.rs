@@ -0,0 +1,136 @@
+use actix_web::{web, HttpResponse};
+use chrono::Utc;
+use serde::Deserialize;
+use std::collections::HashSet;
+use uuid::Uuid;
+
+use crate::models::{ApiResponse, Job, Notification, NotificationKind};
+use crate::state::{AppState, save_notifications};
+use crate::utils::auth::{forbidden, AuthUser};
+use crate::utils::matching::compute_weighted_match;
+use crate::utils::text::strip_html;
+
+// Minimum weighted match score for a synced job to be announced to a user
+const HIGH_MATCH_THRESHOLD: f64 = 75.0;
+
+#[derive(Deserialize)]
+pub struct NotificationQuery {
+    pub unread_only: Option<bool>,
+    pub limit: Option<usize>,
+}
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg
+        .route("/notifications/users/{user_id}", web::get().to(list_notifications))
+        .route("/notifications/users/{user_id}/read-all", web::put().to(mark_all_read))
+        .route("/notifications/{id}/read", web::put().to(mark_read))
+        .route("/notifications/{id}", web::delete().to(delete_notification));
+}
+
+/// Append a notification to a user's inbox and persist it.
+pub fn push_notification(state: &AppState, user_id: &str, kind: NotificationKind, title: String, description: String, metadata: Option<serde_json::Value>) {
+    let mut notifications = state.notifications.lock().unwrap();
+    notifications.push(Notification {
+        id: Uuid::new_v4().to_string(),
+        user_id: user_id.to_string(),
+        kind,
+        title,
+        description,
+        timestamp: Utc::now().to_rfc3339(),
+        read: false,
+        metadata,
+    });
+    let _ = save_notifications(&notifications);
+}
+
+/// Tell each user about freshly synced jobs that score highly against their skills.
+/// Jobs a user was already told about (by external id) are skipped.
+pub fn notify_high_match_jobs(state: &AppState, jobs: &[Job]) {
+    if jobs.is_empty() { return; }
+    let users = state.users.lock().unwrap().clone();
+    let idf = state.idf.lock().unwrap().clone();
+
+    for user in users.iter().filter(|u| !u.skills.is_empty()) {
+        let already_notified: HashSet<String> = {
+            let notifications = state.notifications.lock().unwrap();
+            notifications.iter()
+                .filter(|n| n.user_id == user.id && n.kind == NotificationKind::JobMatch)
+                .filter_map(|n| n.metadata.as_ref()?.get("job_ids")?.as_array().cloned())
+                .flatten()
+                .filter_map(|v| v.as_str().map(|s| s.to_string()))
+                .collect()
+        };
+
+        let mut matches: Vec<(f64, &Job)> = jobs.iter()
+            .filter(|j| !already_notified.contains(j.external_id.as_deref().unwrap_or(&j.id)))
+            .map(|j| (compute_weighted_match(&user.skills, &format!("{}\n{}", j.title, strip_html(&j.description)), &idf).0, j))
+            .filter(|(score, _)| *score >= HIGH_MATCH_THRESHOLD)
+            .collect();
+        if matches.is_empty() { continue; }
+        matches.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+
+        let best = matches[0].1;
+        let title = if matches.len() == 1 {
+            format!("New match: {} at {}", best.title, best.company)
+        } else {
+            format!("{} new jobs match your skills", matches.len())
+        };
+        let description = format!("Top match: {} at {} ({}% match).", best.title, best.company, matches[0].0 as i32);
+        let job_ids: Vec<String> = matches.iter().map(|(_, j)| j.external_id.clone().unwrap_or_else(|| j.id.clone())).collect();
+        push_notification(state, &user.id, NotificationKind::JobMatch, title, description, Some(serde_json::json!({ "job_ids": job_ids })));
+    }
+}
+
+async fn list_notifications(state: web::Data<AppState>, auth: AuthUser, path: web::Path<String>, query: web::Query<NotificationQuery>) -> HttpResponse {
+    let user_id = path.into_inner();
+    if !auth.can_access(&user_id) { return forbidden(); }
+    let notifications = state.notifications.lock().unwrap();
+    let mine: Vec<&Notification> = notifications.iter().filter(|n| n.user_id == user_id).collect();
+    let unread_count = mine.iter().filter(|n| !n.read).count();
+    let mut results: Vec<Notification> = mine.into_iter()
+        .filter(|n| !query.unread_only.unwrap_or(false) || !n.read)
+        .cloned()
+        .collect();
+    results.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+    results.truncate(query.limit.unwrap_or(50));
+
+    HttpResponse::Ok().json(ApiResponse { success: true, data: Some(serde_json::json!({ "notifications": results, "unread_count": unread_count })), message: "Notifications fetched".into() })
+}
+
+async fn mark_read(state: web::Data<AppState>, auth: AuthUser, path: web::Path<String>) -> HttpResponse {
+    let id = path.into_inner();
+    let mut notifications = state.notifications.lock().unwrap();
+    let updated = match notifications.iter_mut().find(|n| n.id == id) {
+        Some(n) if !auth.can_access(&n.user_id) => return forbidden(),
+        Some(n) => { n.read = true; n.clone() }
+        None => return HttpResponse::NotFound().json(ApiResponse::<serde_json::Value> { success: false, data: None, message: "Notification not found".into() }),
+    };
+    let _ = save_notifications(&notifications);
+    HttpResponse::Ok().json(ApiResponse { success: true, data: Some(updated), message: "Notification marked as read".into() })
+}
+
+async fn mark_all_read(state: web::Data<AppState>, auth: AuthUser, path: web::Path<String>) -> HttpResponse {
+    let user_id = path.into_inner();
+    if !auth.can_access(&user_id) { return forbidden(); }
+    let mut notifications = state.notifications.lock().unwrap();
+    let mut marked = 0;
+    for n in notifications.iter_mut().filter(|n| n.user_id == user_id && !n.read) {
+        n.read = true;
+        marked += 1;
+    }
+    let _ = save_notifications(&notifications);
+    HttpResponse::Ok().json(ApiResponse { success: true, data: Some(serde_json::json!({ "marked": marked })), message: "All notifications marked as read".into() })
+}
+
+async fn delete_notification(state: web::Data<AppState>, auth: AuthUser, path: web::Path<String>) -> HttpResponse {
+    let id = path.into_inner();
+    let mut notifications = state.notifications.lock().unwrap();
+    match notifications.iter().find(|n| n.id == id) {
+        Some(n) if !auth.can_access(&n.user_id) => return forbidden(),
+        Some(_) => {}
+        None => return HttpResponse::NotFound().json(ApiResponse::<serde_json::Value> { success: false, data: None, message: "Notification not found".into() }),
+    }
+    notifications.retain(|n| n.id != id);
+    let _ = save_notifications(&notifications);
+    HttpResponse::Ok().json(ApiResponse::<serde_json::Value> { success: true, data: None, message: "Notification deleted".into() })
+}
@@ -4,7 +4,6 @@ use futures::{StreamExt, TryStreamExt};
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::Path;
-use uuid::Uuid;
 use chrono::Utc;
 use reqwest::Client;
 use std::time::Duration;
@@ -12,8 +11,14 @@ use std::process::Command;
 use std::collections::HashMap;
 use tokio::io::AsyncWriteExt; // added for async file writes
 
-use crate::models::{ApiResponse, Job, JobRecommendation};
+use crate::models::{ApiResponse, Job, JobRecommendation, NotificationKind};
 use crate::state::AppState;
+use crate::utils::auth::{forbidden, AuthUser};
+use crate::utils::matching::COMMON_TECH_SKILLS;
+use crate::utils::text::strip_html;
+use crate::routes::notifications::push_notification;
+use crate::routes::users::record_recommendations;
+use crate::utils::uploads::sanitize_filename;
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct ResumeProcessResponse {
@@ -35,7 +40,7 @@ pub fn config(cfg: &mut web::ServiceConfig) {
 /// 2. Extracts text content from the PDF
 /// 3. Sends the extracted content to the ML service
 /// 4. Returns matching jobs with their match percentages
-async fn process_resume(mut payload: Multipart, state: web::Data<AppState>) -> Result<HttpResponse> {
+async fn process_resume(mut payload: Multipart, state: web::Data<AppState>, auth: AuthUser) -> Result<HttpResponse> {
     println!("📄 Processing uploaded resume...");
 
     let mut user_id = String::new();
@@ -140,6 +145,10 @@ async fn process_resume(mut payload: Multipart, state: web::Data<AppState>) -> R
             message: "User ID is required".to_string(),
         }));
     }
+    if !auth.can_access(&user_id) {
+        if let Some(p) = &file_path { let _ = fs::remove_file(p); }
+        return Ok(forbidden());
+    }
 
     // Validate file upload or text content
     if file_path.is_none() && resume_text_override.as_deref().unwrap_or("").is_empty() {
@@ -182,6 +191,14 @@ async fn process_resume(mut payload: Multipart, state: web::Data<AppState>) -> R
         }
     };
 
+    if state.users.lock().unwrap().iter().any(|u| u.id == user_id) {
+        let description = match recommendations.first() {
+            Some(top) => format!("Found {} matching jobs. Top match: {} at {} ({}%).", recommendations.len(), top.job.title, top.job.company, top.match_percentage),
+            None => "No matching jobs found yet. Try adding more skills to your profile.".to_string(),
+        };
+        push_notification(&state, &user_id, NotificationKind::ResumeProcessed, "Resume processed".to_string(), description, Some(serde_json::json!({ "matches": recommendations.len() })));
+    }
+
     // Return response with recommendations and extracted text
     Ok(HttpResponse::Ok().json(ApiResponse {
         success: true,
@@ -234,7 +251,7 @@ async fn get_job_recommendations(
     let prediction_url = format!("{}/predict", ml_service_url);
 
     // Gather all available jobs to match against
-    let jobs = fetch_jobs().await?;
+    let jobs = fetch_jobs(state)?;
     let mut recommendations = Vec::new();
 
     // Get user skills if any
@@ -318,10 +335,16 @@ async fn get_job_recommendations(
     }
 
     // If no recommendations (possibly due to ML service failure), generate some basic ones
-    if recommendations.is_empty() {
+    let used_fallback = recommendations.is_empty();
+    if used_fallback {
         println!("⚠️ No recommendations from ML service, generating fallback matches");
         recommendations = generate_fallback_recommendations(&jobs, resume_text);
     }
+    {
+        let mut m = state.metrics.lock().unwrap();
+        if used_fallback { m.resume_fallback_matches += 1; } else { m.resume_ml_matches += 1; }
+    }
+    record_recommendations(state, recommendations.len());
 
     Ok(recommendations)
 }
@@ -332,11 +355,7 @@ fn generate_fallback_recommendations(jobs: &Vec<Job>, resume_text: &str) -> Vec<
     let mut scores = HashMap::new();
 
     // List of common tech skills to match against
-    let tech_skills = vec![
-        "rust", "python", "javascript", "typescript", "react", "node.js", "docker",
-        "kubernetes", "aws", "gcp", "sql", "mongodb", "redis", "git", "linux",
-        "java", "c++", "go", "html", "css", "angular", "vue", "express"
-    ];
+    let tech_skills = COMMON_TECH_SKILLS;
 
     // Calculate simple match scores based on keyword frequency
     for job in jobs {
@@ -390,96 +409,16 @@ fn generate_fallback_recommendations(jobs: &Vec<Job>, resume_text: &str) -> Vec<
     recommendations
 }
 
-/// Fetch jobs from the jobs service
-async fn fetch_jobs() -> Result<Vec<Job>, String> {
-    let client = Client::builder()
-        .timeout(Duration::from_secs(30))
-        .build()
-        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
-
-    // Use the Remotive API to fetch jobs
-    let url = "https://remotive.com/api/remote-jobs?limit=50";
-
-    let response = client
-        .get(url)
-        .header("User-Agent", "JobLens-Backend/1.0")
-        .send()
-        .await
-        .map_err(|e| format!("Failed to fetch jobs: {}", e))?;
-
-    if !response.status().is_success() {
-        return Err(format!("Failed to fetch jobs: HTTP {}", response.status()));
-    }
-
-    #[derive(Deserialize, Debug)]
-    struct RemotiveJob {
-        id: i32,
-        url: String,
-        title: String,
-        company_name: String,
-        company_logo: Option<String>,
-        category: String,
-        job_type: Option<String>,
-        publication_date: String,
-        candidate_required_location: Option<String>,
-        salary: Option<String>,
-        description: String,
-    }
-
-    #[derive(Deserialize, Debug)]
-    struct RemotiveResponse {
-        #[serde(rename = "job-count")]
-        job_count: i32,
-        jobs: Vec<RemotiveJob>,
-    }
-
-    let remotive_response: RemotiveResponse = response
-        .json()
-        .await
-        .map_err(|e| format!("Failed to parse jobs: {}", e))?;
-
-    let jobs: Vec<Job> = remotive_response.jobs
-        .into_iter()
-        .map(|rj| Job {
-            id: Uuid::new_v4().to_string(),
-            title: rj.title,
-            company: rj.company_name,
-            company_logo: rj.company_logo,
-            location: rj.candidate_required_location,
-            url: rj.url,
-            description: rj.description,
-            created_at: Utc::now().to_rfc3339(),
-            external_id: Some(rj.id.to_string()),
-            job_type: rj.job_type,
-            salary: rj.salary,
-            category: Some(rj.category),
-        })
+/// The newest active jobs from the synced catalog
+fn fetch_jobs(state: &AppState) -> Result<Vec<Job>, String> {
+    let mut jobs: Vec<Job> = state.jobs_cache.lock().unwrap().iter()
+        .filter(|j| j.is_listed())
+        .cloned()
         .collect();
-
-    Ok(jobs)
-}
-
-/// Sanitize filename to prevent path traversal and other issues
-fn sanitize_filename(filename: &str) -> String {
-    let filename = Path::new(filename)
-        .file_name()
-        .and_then(|name| name.to_str())
-        .unwrap_or("unnamed.pdf");
-
-    // Replace potentially dangerous characters
-    let re = regex::Regex::new(r"[^a-zA-Z0-9_.%-]").unwrap();
-    let sanitized = re.replace_all(filename, "_").to_string();
-
-    // Ensure filename isn't too long
-    if sanitized.len() > 100 {
-        sanitized[..100].to_string()
-    } else {
-        sanitized
+    if jobs.is_empty() {
+        return Err("The job catalog is empty; run a job sync first".to_string());
     }
-}
-
-/// Strip HTML tags from a string to plain text
-fn strip_html(input: &str) -> String {
-    let re = regex::Regex::new(r"<[^>]+>").unwrap();
-    re.replace_all(input, " ").to_string()
+    jobs.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    jobs.truncate(50);
+    Ok(jobs)
 }
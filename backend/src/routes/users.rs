@@ -1,14 +1,16 @@
 use actix_multipart::Multipart;
 use actix_web::{web, HttpResponse};
-use futures_util::TryStreamExt as _;
 use serde::Deserialize;
 use std::fs;
-use std::io::Write;
 use chrono::Utc;
 
-use crate::models::{ApiResponse, User};
-use crate::state::{AppState, save_users, load_jobs_cache};
+use crate::models::{ApiResponse, Role, User, UserFeedback};
+use crate::state::AppState;
+use crate::storage::save_failed;
+use crate::utils::auth::{forbidden, AuthUser};
 use crate::utils::matching::compute_weighted_match;
+use crate::utils::ranking::{describe_adjustments, FeedbackProfile};
+use crate::utils::uploads::{save_multipart_file, serve_resume_file};
 
 #[derive(Deserialize)]
 pub struct UpdateUserPayload {
@@ -22,10 +24,23 @@ pub struct UpdateUserPayload {
 #[derive(Deserialize)]
 pub struct RecQuery { pub limit: Option<usize> }
 
+#[derive(Deserialize)]
+pub struct ListUsersQuery {
+    pub q: Option<String>,
+    pub role: Option<String>,
+    pub limit: Option<usize>,
+    pub offset: Option<usize>,
+}
+
+#[derive(Deserialize)]
+pub struct RolePayload { pub role: String }
+
 pub fn config(cfg: &mut web::ServiceConfig) {
     cfg
+        .route("/users", web::get().to(list_users))
         .route("/users/{id}", web::get().to(get_user))
         .route("/users/{id}", web::put().to(update_user))
+        .route("/users/{id}/role", web::put().to(update_user_role))
         .route("/users/{id}/resume", web::post().to(upload_user_resume))
         .route("/users/{id}/resume", web::get().to(get_user_resume))
         .route("/users/{id}/resume/match", web::post().to(upload_resume_for_matching))
@@ -33,8 +48,70 @@ pub fn config(cfg: &mut web::ServiceConfig) {
         .route("/users/{id}/recommendations/skills", web::post().to(get_ai_job_recommendations));
 }
 
-async fn get_user(state: web::Data<AppState>, path: web::Path<String>) -> HttpResponse {
+/// Admin only: every account, optionally narrowed by `role` and an email or name match on `q`.
+async fn list_users(state: web::Data<AppState>, auth: AuthUser, query: web::Query<ListUsersQuery>) -> HttpResponse {
+    if !auth.is_admin() { return forbidden(); }
+    let role = match query.role.as_deref().map(|r| (r, Role::parse(r))) {
+        Some((r, None)) => return HttpResponse::BadRequest().json(ApiResponse::<serde_json::Value> { success: false, data: None, message: format!("Unknown role: {}", r) }),
+        Some((_, role)) => role,
+        None => None,
+    };
+    let needle = query.q.as_deref().map(|q| q.trim().to_lowercase()).filter(|q| !q.is_empty());
+    let limit = query.limit.unwrap_or(50).clamp(1, 200);
+    let offset = query.offset.unwrap_or(0);
+
+    let users = state.users.lock().unwrap();
+    let matching: Vec<&User> = users.iter()
+        .filter(|u| role.map(|r| u.role == r).unwrap_or(true))
+        .filter(|u| needle.as_ref().map(|n| {
+            u.email.to_lowercase().contains(n) || format!("{} {}", u.first_name, u.last_name).to_lowercase().contains(n)
+        }).unwrap_or(true))
+        .collect();
+    let page: Vec<User> = matching.iter().skip(offset).take(limit)
+        .map(|u| { let mut u = (*u).clone(); u.password_hash = None; u })
+        .collect();
+    HttpResponse::Ok().json(ApiResponse { success: true, data: Some(serde_json::json!({
+        "users": page,
+        "total": matching.len(),
+        "limit": limit,
+        "offset": offset,
+    })), message: "Users fetched".into() })
+}
+
+/// Admin only: make an account a user or an admin. A demotion applies at once, a
+/// promotion from that account's next login or token refresh.
+async fn update_user_role(state: web::Data<AppState>, auth: AuthUser, path: web::Path<String>, payload: web::Json<RolePayload>) -> HttpResponse {
+    if !auth.is_admin() { return forbidden(); }
+    let id = path.into_inner();
+    let role = match Role::parse(&payload.role) {
+        Some(r) => r,
+        None => return HttpResponse::BadRequest().json(ApiResponse::<serde_json::Value> { success: false, data: None, message: format!("Unknown role: {}", payload.role) }),
+    };
+    // Keeps the last admin from locking everyone out
+    if id == auth.user_id && role != Role::Admin {
+        return HttpResponse::BadRequest().json(ApiResponse::<serde_json::Value> { success: false, data: None, message: "Admins can't remove their own admin role".into() });
+    }
+    let (updated, saved) = {
+        let mut users = state.users.lock().unwrap();
+        let updated = match users.iter_mut().find(|u| u.id == id) {
+            Some(u) => {
+                u.role = role;
+                u.updated_at = Utc::now().to_rfc3339();
+                let mut resp = u.clone(); resp.password_hash = None;
+                resp
+            }
+            None => return HttpResponse::NotFound().json(ApiResponse::<serde_json::Value> { success: false, data: None, message: "User not found".into() }),
+        };
+        (updated, state.storage.save_users(&users, &[id.as_str()]))
+    };
+    if let Err(e) = saved.await { return save_failed(e); }
+    println!("🔑 {} set role of {} to {}", auth.user_id, id, role.as_str());
+    HttpResponse::Ok().json(ApiResponse { success: true, data: Some(updated), message: "Role updated".into() })
+}
+
+async fn get_user(state: web::Data<AppState>, auth: AuthUser, path: web::Path<String>) -> HttpResponse {
     let id = path.into_inner();
+    if !auth.can_access(&id) { return forbidden(); }
     let users = state.users.lock().unwrap();
     if let Some(u) = users.iter().find(|u| u.id == id) {
         let mut resp = u.clone();
@@ -44,73 +121,77 @@ async fn get_user(state: web::Data<AppState>, path: web::Path<String>) -> HttpRe
     HttpResponse::NotFound().json(ApiResponse::<serde_json::Value> { success: false, data: None, message: "User not found".into() })
 }
 
-async fn update_user(state: web::Data<AppState>, path: web::Path<String>, payload: web::Json<UpdateUserPayload>) -> HttpResponse {
+async fn update_user(state: web::Data<AppState>, auth: AuthUser, path: web::Path<String>, payload: web::Json<UpdateUserPayload>) -> HttpResponse {
     let id = path.into_inner();
-    let mut users = state.users.lock().unwrap();
-    let mut updated: Option<User> = None;
-    if let Some(u) = users.iter_mut().find(|u| u.id == id) {
-        if let Some(v) = &payload.first_name { u.first_name = v.clone(); }
-        if let Some(v) = &payload.last_name { u.last_name = v.clone(); }
-        if let Some(v) = &payload.skills { u.skills = v.clone(); }
-        if let Some(v) = payload.experience_years { u.experience_years = v; }
-        if let Some(v) = &payload.location { u.location = v.clone(); }
-        u.updated_at = Utc::now().to_rfc3339();
-        updated = Some(u.clone());
-    }
-    let _ = save_users(&users);
+    if !auth.can_access(&id) { return forbidden(); }
+    let (updated, saved) = {
+        let mut users = state.users.lock().unwrap();
+        let mut updated: Option<User> = None;
+        if let Some(u) = users.iter_mut().find(|u| u.id == id) {
+            if let Some(v) = &payload.first_name { u.first_name = v.clone(); }
+            if let Some(v) = &payload.last_name { u.last_name = v.clone(); }
+            if let Some(v) = &payload.skills { u.skills = v.clone(); }
+            if let Some(v) = payload.experience_years { u.experience_years = v; }
+            if let Some(v) = &payload.location { u.location = v.clone(); }
+            u.updated_at = Utc::now().to_rfc3339();
+            updated = Some(u.clone());
+        }
+        (updated, state.storage.save_users(&users, &[id.as_str()]))
+    };
+    if let Err(e) = saved.await { return save_failed(e); }
 
     if let Some(mut resp) = updated { resp.password_hash = None; return HttpResponse::Ok().json(ApiResponse { success: true, data: Some(resp), message: "User updated".into() }); }
     HttpResponse::NotFound().json(ApiResponse::<serde_json::Value> { success: false, data: None, message: "User not found".into() })
 }
 
-async fn upload_user_resume(state: web::Data<AppState>, path: web::Path<String>, mut payload: Multipart) -> Result<HttpResponse, actix_web::Error> {
+async fn upload_user_resume(state: web::Data<AppState>, auth: AuthUser, path: web::Path<String>, mut payload: Multipart) -> Result<HttpResponse, actix_web::Error> {
     let user_id = path.into_inner();
-    let mut file_path: Option<std::path::PathBuf> = None;
-
-    while let Some(item) = payload.try_next().await? {
-        let mut field = item;
-        let content_disposition = field.content_disposition().clone();
-        let filename = content_disposition.get_filename().map(|f| f.to_string()).unwrap_or_else(|| "resume.pdf".into());
-        let sanitized = filename.replace('/', "_").replace('\\', "_");
-        let target_name = format!("{}_{}_{}", user_id, Utc::now().timestamp(), sanitized);
-        let save_path = state.uploads_dir.join(target_name);
-        let mut f = fs::File::create(&save_path).map_err(actix_web::error::ErrorInternalServerError)?;
-        while let Some(chunk) = field.try_next().await? { f.write_all(&chunk).map_err(actix_web::error::ErrorInternalServerError)?; }
-        file_path = Some(save_path);
-    }
+    if !auth.can_access(&user_id) { return Ok(forbidden()); }
+    let saved = save_multipart_file(&mut payload, &state.uploads_dir, &user_id).await?;
 
-    if let Some(path) = file_path {
-        let mut users = state.users.lock().unwrap();
-        if let Some(u) = users.iter_mut().find(|u| u.id == user_id) {
-            u.resume_filename = Some(path.file_name().unwrap().to_string_lossy().to_string());
-            u.updated_at = Utc::now().to_rfc3339();
+    if let Some(upload) = saved {
+        let (recorded, saved) = {
+            let mut users = state.users.lock().unwrap();
+            let recorded = match users.iter_mut().find(|u| u.id == user_id) {
+                Some(u) => {
+                    u.resume_filename = Some(upload.filename.clone());
+                    u.updated_at = Utc::now().to_rfc3339();
+                    Some(state.storage.record_resume(upload.resume_record(&user_id, None, None)))
+                }
+                None => None,
+            };
+            (recorded, state.storage.save_users(&users, &[user_id.as_str()]))
+        };
+        if let Some(recorded) = recorded {
+            if let Err(e) = recorded.await { return Ok(save_failed(e)); }
         }
-        let _ = save_users(&users);
-        let meta = serde_json::json!({ "filename": path.file_name().unwrap().to_string_lossy(), "uploaded_at": Utc::now().to_rfc3339() });
+        if let Err(e) = saved.await { return Ok(save_failed(e)); }
+        let meta = serde_json::json!({ "filename": upload.filename, "uploaded_at": Utc::now().to_rfc3339() });
         Ok(HttpResponse::Ok().json(ApiResponse { success: true, data: Some(meta), message: "Resume uploaded".into() }))
     } else {
         Ok(HttpResponse::BadRequest().json(ApiResponse::<serde_json::Value> { success: false, data: None, message: "No file received".into() }))
     }
 }
 
-async fn get_user_resume(state: web::Data<AppState>, path: web::Path<String>) -> HttpResponse {
+async fn get_user_resume(state: web::Data<AppState>, auth: AuthUser, path: web::Path<String>) -> HttpResponse {
     let user_id = path.into_inner();
+    if !auth.can_access(&user_id) { return forbidden(); }
     let users = state.users.lock().unwrap();
     if let Some(u) = users.iter().find(|u| u.id == user_id) {
         if let Some(fname) = &u.resume_filename {
             let full = state.uploads_dir.join(fname);
             if full.exists() {
-                let mime = mime_guess::from_path(&full).first_or_octet_stream();
                 let data = match fs::read(&full) { Ok(d) => d, Err(_) => vec![] };
-                return HttpResponse::Ok().insert_header((actix_web::http::header::CONTENT_TYPE, mime.to_string())).body(data);
+                return serve_resume_file(fname, fname, data);
             }
         }
     }
     HttpResponse::NotFound().finish()
 }
 
-async fn user_recommendations(state: web::Data<AppState>, path: web::Path<String>, query: web::Query<RecQuery>) -> HttpResponse {
+async fn user_recommendations(state: web::Data<AppState>, auth: AuthUser, path: web::Path<String>, query: web::Query<RecQuery>) -> HttpResponse {
     let user_id = path.into_inner();
+    if !auth.can_access(&user_id) { return forbidden(); }
     let limit = query.limit.unwrap_or(10);
 
     let user_opt = { let users = state.users.lock().unwrap(); users.iter().find(|u| u.id == user_id).cloned() };
@@ -118,18 +199,31 @@ async fn user_recommendations(state: web::Data<AppState>, path: web::Path<String
     let user = user_opt.unwrap();
 
     let mut jobs = state.jobs_cache.lock().unwrap().clone();
-    if jobs.is_empty() { jobs = load_jobs_cache(); }
+    jobs.retain(|j| j.is_listed());
+
+    let user_feedback: Vec<UserFeedback> = state.feedback.lock().unwrap().iter().filter(|f| f.user_id == user_id).cloned().collect();
+    let profile = FeedbackProfile::build(&user_feedback, &jobs);
 
     let idf = state.idf.lock().unwrap();
     let mut scored: Vec<(f64, serde_json::Value)> = vec![];
     for j in jobs.into_iter() {
+        if profile.is_dismissed(&j) { continue; }
         let text = format!("{}\n{}", j.title, j.description);
-        let (score, matching, missing) = compute_weighted_match(&user.skills, &text, &idf);
-        if score > 0.0 {
+        let (base_score, matching, missing) = compute_weighted_match(&user.skills, &text, &idf);
+        if base_score > 0.0 {
+            let (multiplier, adjustments) = profile.adjust(&j);
+            let score = (base_score * multiplier).min(100.0);
+            let explanation = if profile.is_empty() {
+                "Weighted skill overlap using dataset IDF model".to_string()
+            } else {
+                describe_adjustments(base_score, &adjustments)
+            };
             let rec = serde_json::json!({
                 "job": j,
                 "match_score": (score as i32),
-                "explanation": "Weighted skill overlap using dataset IDF model",
+                "base_score": (base_score as i32),
+                "explanation": explanation,
+                "score_adjustments": adjustments,
                 "matching_skills": matching,
                 "missing_skills": missing,
             });
@@ -138,42 +232,49 @@ async fn user_recommendations(state: web::Data<AppState>, path: web::Path<String
     }
     scored.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
     let results: Vec<serde_json::Value> = scored.into_iter().take(limit).map(|(_, v)| v).collect();
+    record_recommendations(&state, results.len());
 
     HttpResponse::Ok().json(ApiResponse { success: true, data: Some(serde_json::json!(results)), message: "Recommendations".into() })
 }
 
-async fn upload_resume_for_matching(state: web::Data<AppState>, path: web::Path<String>, mut payload: Multipart) -> Result<HttpResponse, actix_web::Error> {
+pub(crate) fn record_recommendations(state: &AppState, served: usize) {
+    let mut m = state.metrics.lock().unwrap();
+    m.recommendation_requests += 1;
+    m.recommendations_served += served as u64;
+}
+
+async fn upload_resume_for_matching(state: web::Data<AppState>, auth: AuthUser, path: web::Path<String>, mut payload: Multipart) -> Result<HttpResponse, actix_web::Error> {
     let user_id = path.into_inner();
-    let mut file_path: Option<std::path::PathBuf> = None;
-
-    while let Some(item) = payload.try_next().await? {
-        let mut field = item;
-        let content_disposition = field.content_disposition().clone();
-        let filename = content_disposition.get_filename().map(|f| f.to_string()).unwrap_or_else(|| "resume.pdf".into());
-        let sanitized = filename.replace('/', "_").replace('\\', "_");
-        let target_name = format!("{}_{}_{}", user_id, Utc::now().timestamp(), sanitized);
-        let save_path = state.uploads_dir.join(target_name);
-        let mut f = fs::File::create(&save_path).map_err(actix_web::error::ErrorInternalServerError)?;
-        while let Some(chunk) = field.try_next().await? { f.write_all(&chunk).map_err(actix_web::error::ErrorInternalServerError)?; }
-        file_path = Some(save_path);
-    }
+    if !auth.can_access(&user_id) { return Ok(forbidden()); }
+    let saved = save_multipart_file(&mut payload, &state.uploads_dir, &user_id).await?;
 
-    if let Some(path) = file_path {
-        let mut users = state.users.lock().unwrap();
-        if let Some(u) = users.iter_mut().find(|u| u.id == user_id) {
-            u.resume_filename = Some(path.file_name().unwrap().to_string_lossy().to_string());
-            u.updated_at = Utc::now().to_rfc3339();
+    if let Some(upload) = saved {
+        let (recorded, saved) = {
+            let mut users = state.users.lock().unwrap();
+            let recorded = match users.iter_mut().find(|u| u.id == user_id) {
+                Some(u) => {
+                    u.resume_filename = Some(upload.filename.clone());
+                    u.updated_at = Utc::now().to_rfc3339();
+                    Some(state.storage.record_resume(upload.resume_record(&user_id, None, None)))
+                }
+                None => None,
+            };
+            (recorded, state.storage.save_users(&users, &[user_id.as_str()]))
+        };
+        if let Some(recorded) = recorded {
+            if let Err(e) = recorded.await { return Ok(save_failed(e)); }
         }
-        let _ = save_users(&users);
-        let meta = serde_json::json!({ "filename": path.file_name().unwrap().to_string_lossy(), "uploaded_at": Utc::now().to_rfc3339() });
+        if let Err(e) = saved.await { return Ok(save_failed(e)); }
+        let meta = serde_json::json!({ "filename": upload.filename, "uploaded_at": Utc::now().to_rfc3339() });
         Ok(HttpResponse::Ok().json(ApiResponse { success: true, data: Some(meta), message: "Resume uploaded and will be used for matching".into() }))
     } else {
         Ok(HttpResponse::BadRequest().json(ApiResponse::<serde_json::Value> { success: false, data: None, message: "No file received".into() }))
     }
 }
 
-async fn get_ai_job_recommendations(state: web::Data<AppState>, path: web::Path<String>, payload: web::Json<RecQuery>) -> HttpResponse {
+async fn get_ai_job_recommendations(state: web::Data<AppState>, auth: AuthUser, path: web::Path<String>, payload: web::Json<RecQuery>) -> HttpResponse {
     let user_id = path.into_inner();
+    if !auth.can_access(&user_id) { return forbidden(); }
     let limit = payload.limit.unwrap_or(10);
 
     let user_opt = { let users = state.users.lock().unwrap(); users.iter().find(|u| u.id == user_id).cloned() };
@@ -183,10 +284,11 @@ async fn get_ai_job_recommendations(state: web::Data<AppState>, path: web::Path<
     // Here you would integrate with the AI model for recommendations
     // For now, we will just return the top N jobs based on some criteria
     let mut jobs = state.jobs_cache.lock().unwrap().clone();
-    if jobs.is_empty() { jobs = load_jobs_cache(); }
+    jobs.retain(|j| j.is_listed());
 
     // Dummy AI integration: just take the first `limit` jobs
     let results: Vec<serde_json::Value> = jobs.into_iter().take(limit).map(|j| serde_json::json!({ "job": j, "match_score": 100, "explanation": "Top AI recommendation" })).collect();
+    record_recommendations(&state, results.len());
 
     HttpResponse::Ok().json(ApiResponse { success: true, data: Some(serde_json::json!(results)), message: "AI Recommendations".into() })
 }
@@ -0,0 +1,177 @@
+use serde::Deserialize;
+use serde_json::Value;
+
+use super::{new_job, parse_date, JobSource};
+use crate::models::Job;
+
+/// Dotted paths (e.g. `company.name`) from a posting object to each `Job` field.
+/// Defaults match the most common key names; `external_id` falls back to `url`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct FieldMapping {
+    pub external_id: String,
+    pub title: String,
+    pub company: String,
+    pub url: String,
+    pub description: String,
+    pub company_logo: Option<String>,
+    pub location: Option<String>,
+    pub created_at: Option<String>,
+    pub job_type: Option<String>,
+    pub salary: Option<String>,
+    pub category: Option<String>,
+}
+
+impl Default for FieldMapping {
+    fn default() -> Self {
+        FieldMapping {
+            external_id: "id".into(),
+            title: "title".into(),
+            company: "company".into(),
+            url: "url".into(),
+            description: "description".into(),
+            company_logo: None,
+            location: Some("location".into()),
+            created_at: Some("created_at".into()),
+            job_type: Some("job_type".into()),
+            salary: Some("salary".into()),
+            category: Some("category".into()),
+        }
+    }
+}
+
+/// Any JSON document holding an array of postings, mapped field by field.
+pub struct JsonFeedSource {
+    pub name: String,
+    pub url: String,
+    pub jobs_path: String,
+    pub fields: FieldMapping,
+}
+
+fn lookup<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
+    path.split('.').filter(|p| !p.is_empty()).try_fold(value, |v, key| match v {
+        Value::Array(items) => key.parse::<usize>().ok().and_then(|i| items.get(i)),
+        _ => v.get(key),
+    })
+}
+
+// Strings and numbers both count; arrays of strings are joined (e.g. tag lists)
+fn text_at(value: &Value, path: &str) -> Option<String> {
+    let text = match lookup(value, path)? {
+        Value::String(s) => s.trim().to_string(),
+        Value::Number(n) => n.to_string(),
+        Value::Bool(b) => b.to_string(),
+        Value::Array(items) => items.iter().filter_map(|i| i.as_str()).collect::<Vec<_>>().join(", "),
+        _ => return None,
+    };
+    if text.is_empty() { None } else { Some(text) }
+}
+
+fn optional(value: &Value, path: &Option<String>) -> Option<String> {
+    path.as_deref().and_then(|p| text_at(value, p))
+}
+
+impl JobSource for JsonFeedSource {
+    fn name(&self) -> &str { &self.name }
+
+    fn location(&self) -> &str { &self.url }
+
+    fn parse(&self, body: &str) -> Result<Vec<Job>, String> {
+        let doc: Value = serde_json::from_str(body).map_err(|e| format!("Failed to parse {} feed: {}", self.name, e))?;
+        let items = lookup(&doc, &self.jobs_path)
+            .and_then(|v| v.as_array())
+            .ok_or_else(|| format!("{} feed has no job array at '{}'", self.name, self.jobs_path))?;
+
+        let f = &self.fields;
+        let mut jobs = vec![];
+        // Postings without a title or any usable identifier are skipped rather than failing the feed
+        for item in items {
+            let title = match text_at(item, &f.title) { Some(t) => t, None => continue };
+            let url = text_at(item, &f.url).unwrap_or_default();
+            let external_id = match text_at(item, &f.external_id).or_else(|| if url.is_empty() { None } else { Some(url.clone()) }) {
+                Some(id) => id,
+                None => continue,
+            };
+            let company = text_at(item, &f.company).unwrap_or_else(|| "Unknown".to_string());
+            let description = text_at(item, &f.description).unwrap_or_default();
+
+            let mut job = new_job(&self.name, &external_id, title, company, url, description);
+            if let Some(created) = optional(item, &f.created_at).and_then(|d| parse_date(&d)) { job.created_at = created; }
+            job.company_logo = optional(item, &f.company_logo);
+            job.location = optional(item, &f.location);
+            job.job_type = optional(item, &f.job_type);
+            job.salary = optional(item, &f.salary);
+            job.category = optional(item, &f.category);
+            jobs.push(job);
+        }
+        Ok(jobs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::SalaryPeriod;
+    use crate::sources::fetch;
+    use crate::utils::ids::stable_job_id;
+
+    // The mapping from config/job_sources.example.json
+    fn source() -> JsonFeedSource {
+        let some = |s: &str| Some(s.to_string());
+        JsonFeedSource {
+            name: "example-json".into(),
+            url: concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/sources/json_feed.json").into(),
+            jobs_path: "data.postings".into(),
+            fields: FieldMapping {
+                external_id: "posting_id".into(),
+                title: "role".into(),
+                company: "employer.name".into(),
+                url: "apply_url".into(),
+                description: "body".into(),
+                company_logo: some("employer.logo"),
+                location: some("where"),
+                created_at: some("posted"),
+                job_type: some("employment"),
+                salary: some("pay"),
+                category: some("tags.0"),
+            },
+        }
+    }
+
+    #[actix_web::test]
+    async fn maps_fixture_postings_by_field_paths() {
+        let jobs = fetch(&source()).await.unwrap();
+        // The posting without a title is skipped
+        assert_eq!(jobs.iter().map(|j| j.title.as_str()).collect::<Vec<_>>(), vec!["Backend Developer (Go)", "Frontend Engineer", "Sr. Rust Engineer"]);
+
+        let go = &jobs[0];
+        assert_eq!(go.id, stable_job_id("example-json", "A-1001"));
+        assert_eq!(go.source.as_deref(), Some("example-json"));
+        assert_eq!(go.company, "Gopher Labs");
+        assert_eq!(go.company_logo.as_deref(), Some("https://example.com/gopher.png"));
+        assert_eq!(go.url, "https://jobs.example.com/A-1001");
+        assert_eq!(go.created_at, "2025-03-01T10:00:00+00:00");
+        assert_eq!(go.job_type.as_deref(), Some("Full-time"));
+        assert_eq!(go.category.as_deref(), Some("Software Development"));
+        assert_eq!(go.location.as_deref(), Some("Remote - US"));
+        assert_eq!(go.location_info.as_ref().unwrap().countries, vec!["US"]);
+        let salary = go.salary_range.as_ref().unwrap();
+        assert_eq!((salary.min, salary.max, salary.currency.as_deref(), salary.period), (Some(110000.0), Some(130000.0), Some("USD"), SalaryPeriod::Year));
+
+        // No posting_id: the URL identifies it; RFC 2822 dates are read too
+        let frontend = &jobs[1];
+        assert_eq!(frontend.external_id.as_deref(), Some("https://jobs.example.com/frontend-engineer"));
+        assert_eq!(frontend.id, stable_job_id("example-json", "https://jobs.example.com/frontend-engineer"));
+        assert_eq!(frontend.created_at, "2025-03-01T12:30:00+00:00");
+        assert_eq!((frontend.location_info.as_ref(), frontend.salary_range.as_ref()), (None, None));
+
+        assert_eq!(jobs[2].location_info.as_ref().unwrap().regions, vec!["europe"]);
+    }
+
+    #[test]
+    fn a_missing_job_array_is_an_error() {
+        let mut s = source();
+        s.jobs_path = "data.jobs".into();
+        assert!(s.parse(r#"{"data": {"postings": []}}"#).is_err());
+    }
+}
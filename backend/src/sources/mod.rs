@@ -0,0 +1,172 @@
+// src/sources/mod.rs
+//
+// Job sources turn an upstream listing into normalized `Job` values. Fetching is
+// shared: a source's location is either an http(s) URL or a local file, so every
+// adapter can be pointed at a fixture under `fixtures/sources/`.
+
+mod json_feed;
+mod remotive;
+mod rss;
+
+pub use json_feed::{FieldMapping, JsonFeedSource};
+pub use remotive::RemotiveSource;
+pub use rss::RssSource;
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+use reqwest::Client;
+use serde::Deserialize;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::models::Job;
+use crate::utils::ids::stable_job_id;
+use crate::utils::location::normalize_location;
+use crate::utils::salary::parse_salary;
+
+pub trait JobSource: Send + Sync {
+    /// Short identifier stored on every job and used to derive its stable id.
+    fn name(&self) -> &str;
+    /// http(s) URL or local file path of the listing.
+    fn location(&self) -> &str;
+    /// Normalize a raw listing body into jobs.
+    fn parse(&self, body: &str) -> Result<Vec<Job>, String>;
+}
+
+/// Load a source's listing from the network or disk and parse it.
+pub async fn fetch(source: &dyn JobSource) -> Result<Vec<Job>, String> {
+    let location = source.location();
+    let body = if location.starts_with("http://") || location.starts_with("https://") {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(60))
+            .build()
+            .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+        let response = client
+            .get(location)
+            .header("User-Agent", "JobLens-Backend/1.0")
+            .send()
+            .await
+            .map_err(|e| format!("Failed to fetch {}: {}", source.name(), e))?;
+        if !response.status().is_success() {
+            return Err(format!("{} returned status: {}", source.name(), response.status()));
+        }
+        response.text().await.map_err(|e| format!("Failed to read {} response: {}", source.name(), e))?
+    } else {
+        let path = location.strip_prefix("file://").unwrap_or(location);
+        std::fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path, e))?
+    };
+    let mut jobs = source.parse(&body)?;
+    for job in jobs.iter_mut() {
+        job.salary_range = job.salary.as_deref().and_then(parse_salary);
+        job.location_info = job.location.as_deref().map(normalize_location);
+    }
+    Ok(jobs)
+}
+
+/// A job with only the required fields set; adapters fill in the rest.
+pub(crate) fn new_job(source: &str, external_id: &str, title: String, company: String, url: String, description: String) -> Job {
+    Job {
+        id: stable_job_id(source, external_id),
+        title,
+        company,
+        company_logo: None,
+        location: None,
+        location_info: None,
+        url,
+        description,
+        created_at: Utc::now().to_rfc3339(),
+        external_id: Some(external_id.to_string()),
+        source: Some(source.to_string()),
+        job_type: None,
+        salary: None,
+        salary_range: None,
+        category: None,
+        expired_at: None,
+        duplicate_of: None,
+        source_urls: vec![],
+    }
+}
+
+/// Parse the date formats feeds commonly use (RFC 3339, RFC 2822, naive ISO) into RFC 3339.
+pub(crate) fn parse_date(value: &str) -> Option<String> {
+    let value = value.trim();
+    if let Ok(t) = DateTime::parse_from_rfc3339(value) {
+        return Some(t.with_timezone(&Utc).to_rfc3339());
+    }
+    if let Ok(t) = DateTime::parse_from_rfc2822(value) {
+        return Some(t.with_timezone(&Utc).to_rfc3339());
+    }
+    for fmt in ["%Y-%m-%dT%H:%M:%S", "%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M:%S"] {
+        if let Ok(t) = NaiveDateTime::parse_from_str(value, fmt) {
+            return Some(t.and_utc().to_rfc3339());
+        }
+    }
+    None
+}
+
+fn default_enabled() -> bool { true }
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SourceConfig {
+    Remotive {
+        #[serde(default = "default_enabled")]
+        enabled: bool,
+        url: Option<String>,
+    },
+    JsonFeed {
+        name: String,
+        url: String,
+        #[serde(default = "default_enabled")]
+        enabled: bool,
+        // Dotted path to the array of postings; empty means the document itself is the array
+        #[serde(default)]
+        jobs_path: String,
+        #[serde(default)]
+        fields: Box<FieldMapping>,
+    },
+    Rss {
+        name: String,
+        url: String,
+        #[serde(default = "default_enabled")]
+        enabled: bool,
+        category: Option<String>,
+    },
+}
+
+#[derive(Debug, Deserialize)]
+struct SourcesFile {
+    sources: Vec<SourceConfig>,
+}
+
+pub fn sources_config_path() -> PathBuf {
+    std::env::var("JOB_SOURCES_CONFIG").map(PathBuf::from).unwrap_or_else(|_| PathBuf::from("./config/job_sources.json"))
+}
+
+/// Enabled sources from the config file, or Remotive alone when there is no config.
+/// Read on every sync so edits apply without a restart.
+pub fn configured_sources() -> Result<Vec<Box<dyn JobSource>>, String> {
+    let path = sources_config_path();
+    if !path.exists() {
+        return Ok(vec![Box::new(RemotiveSource::new(None))]);
+    }
+    let text = std::fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let file: SourcesFile = serde_json::from_str(&text).map_err(|e| format!("Invalid {}: {}", path.display(), e))?;
+
+    let mut sources: Vec<Box<dyn JobSource>> = vec![];
+    for cfg in file.sources {
+        match cfg {
+            SourceConfig::Remotive { enabled: true, url } => sources.push(Box::new(RemotiveSource::new(url))),
+            SourceConfig::JsonFeed { name, url, enabled: true, jobs_path, fields } => {
+                sources.push(Box::new(JsonFeedSource { name, url, jobs_path, fields: *fields }))
+            }
+            SourceConfig::Rss { name, url, enabled: true, category } => sources.push(Box::new(RssSource { name, url, category })),
+            _ => {}
+        }
+    }
+    let mut names: Vec<&str> = sources.iter().map(|s| s.name()).collect();
+    names.sort();
+    if names.windows(2).any(|w| w[0] == w[1]) {
+        return Err(format!("Duplicate source names in {}", path.display()));
+    }
+    Ok(sources)
+}
@@ -0,0 +1,105 @@
+use serde::Deserialize;
+
+use super::{new_job, parse_date, JobSource};
+use crate::models::Job;
+
+const REMOTIVE_URL: &str = "https://remotive.com/api/remote-jobs";
+
+#[derive(Deserialize, Debug)]
+struct RemotiveJob {
+    id: i64,
+    url: String,
+    title: String,
+    company_name: String,
+    company_logo: Option<String>,
+    category: String,
+    job_type: Option<String>,
+    publication_date: String,
+    candidate_required_location: Option<String>,
+    salary: Option<String>,
+    description: String,
+}
+
+#[derive(Deserialize, Debug)]
+struct RemotiveResponse {
+    jobs: Vec<RemotiveJob>,
+}
+
+/// The Remotive public API. No `limit` is passed: expiring missing jobs only
+/// makes sense against the complete listing.
+pub struct RemotiveSource {
+    url: String,
+}
+
+impl RemotiveSource {
+    pub fn new(url: Option<String>) -> Self {
+        RemotiveSource { url: url.unwrap_or_else(|| REMOTIVE_URL.to_string()) }
+    }
+}
+
+impl JobSource for RemotiveSource {
+    fn name(&self) -> &str { "remotive" }
+
+    fn location(&self) -> &str { &self.url }
+
+    fn parse(&self, body: &str) -> Result<Vec<Job>, String> {
+        let response: RemotiveResponse = serde_json::from_str(body)
+            .map_err(|e| format!("Failed to parse Remotive response: {}", e))?;
+        Ok(response.jobs.into_iter().map(|rj| {
+            let mut job = new_job(self.name(), &rj.id.to_string(), rj.title, rj.company_name, rj.url, rj.description);
+            if let Some(created) = parse_date(&rj.publication_date) { job.created_at = created; }
+            job.company_logo = rj.company_logo;
+            job.location = rj.candidate_required_location;
+            job.job_type = rj.job_type;
+            job.salary = rj.salary.filter(|s| !s.trim().is_empty());
+            job.category = Some(rj.category);
+            job
+        }).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::SalaryPeriod;
+    use crate::sources::fetch;
+    use crate::utils::ids::stable_job_id;
+
+    const FIXTURE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/sources/remotive.json");
+
+    #[actix_web::test]
+    async fn parses_the_fixture_into_normalized_jobs() {
+        let jobs = fetch(&RemotiveSource::new(Some(FIXTURE.into()))).await.unwrap();
+        assert_eq!(jobs.len(), 2);
+
+        let rust = &jobs[0];
+        assert_eq!(rust.id, stable_job_id("remotive", "1912345"));
+        assert_eq!(rust.external_id.as_deref(), Some("1912345"));
+        assert_eq!(rust.source.as_deref(), Some("remotive"));
+        assert_eq!(rust.title, "Senior Rust Engineer");
+        assert_eq!(rust.company, "Ferrous Systems");
+        assert_eq!(rust.company_logo.as_deref(), Some("https://remotive.com/job/1912345/logo"));
+        assert_eq!(rust.url, "https://remotive.com/remote-jobs/software-dev/senior-rust-engineer-1912345");
+        assert_eq!(rust.created_at, "2025-03-02T09:15:00+00:00");
+        assert_eq!(rust.job_type.as_deref(), Some("full_time"));
+        assert_eq!(rust.category.as_deref(), Some("Software Development"));
+        assert_eq!(rust.location.as_deref(), Some("Europe"));
+        let location = rust.location_info.as_ref().unwrap();
+        assert!(!location.worldwide);
+        assert_eq!(location.regions, vec!["europe"]);
+        let salary = rust.salary_range.as_ref().unwrap();
+        assert_eq!((salary.min, salary.max, salary.currency.as_deref(), salary.period), (Some(120000.0), Some(150000.0), Some("USD"), SalaryPeriod::Year));
+
+        let analyst = &jobs[1];
+        assert_eq!(analyst.id, stable_job_id("remotive", "1912399"));
+        assert_eq!(analyst.company_logo, None);
+        // An empty salary string is no salary
+        assert_eq!((analyst.salary.as_deref(), analyst.salary_range.as_ref()), (None, None));
+        assert!(analyst.location_info.as_ref().unwrap().worldwide);
+    }
+
+    #[test]
+    fn rejects_a_body_that_is_not_a_listing() {
+        assert!(RemotiveSource::new(None).parse(r#"{"jobs": "none"}"#).is_err());
+    }
+}
@@ -0,0 +1,142 @@
+use roxmltree::{Document, Node};
+
+use super::{new_job, parse_date, JobSource};
+use crate::models::Job;
+
+/// RSS 2.0 `<item>`s or Atom `<entry>`s. Job boards put the company in different
+/// places: a `<company>` element, the author, or a "Company: Title" headline.
+pub struct RssSource {
+    pub name: String,
+    pub url: String,
+    // Applied to entries that don't carry a category of their own
+    pub category: Option<String>,
+}
+
+// Child lookups go by local name so namespaced extensions (dc:creator, job:location) match too
+fn child<'a, 'i>(node: Node<'a, 'i>, name: &str) -> Option<Node<'a, 'i>> {
+    node.children().find(|c| c.is_element() && c.tag_name().name() == name)
+}
+
+fn child_text(node: Node, names: &[&str]) -> Option<String> {
+    names.iter()
+        .filter_map(|n| child(node, n))
+        .map(|c| c.descendants().filter(|d| d.is_text()).filter_map(|d| d.text()).collect::<String>().trim().to_string())
+        .find(|t| !t.is_empty())
+}
+
+fn atom_link(entry: Node) -> Option<String> {
+    entry.children()
+        .filter(|c| c.is_element() && c.tag_name().name() == "link")
+        .find(|c| matches!(c.attribute("rel"), None | Some("alternate")))
+        .and_then(|c| c.attribute("href").map(|h| h.to_string()))
+}
+
+impl RssSource {
+    fn entry_to_job(&self, entry: Node, atom: bool) -> Option<Job> {
+        let headline = child_text(entry, &["title"])?;
+        let url = if atom { atom_link(entry) } else { child_text(entry, &["link"]) }.unwrap_or_default();
+        let external_id = child_text(entry, &["guid", "id"]).or_else(|| if url.is_empty() { None } else { Some(url.clone()) })?;
+
+        let author = if atom { child(entry, "author").and_then(|a| child_text(a, &["name"])) } else { child_text(entry, &["creator", "author"]) };
+        let (company, title) = match child_text(entry, &["company", "company_name"]).or(author) {
+            Some(c) => (c, headline),
+            None => match headline.split_once(": ") {
+                Some((c, t)) => (c.trim().to_string(), t.trim().to_string()),
+                None => ("Unknown".to_string(), headline),
+            },
+        };
+        let description = child_text(entry, &["content", "encoded", "description", "summary"]).unwrap_or_default();
+
+        let mut job = new_job(&self.name, &external_id, title, company, url, description);
+        if let Some(created) = child_text(entry, &["pubDate", "published", "updated", "date"]).and_then(|d| parse_date(&d)) {
+            job.created_at = created;
+        }
+        job.location = child_text(entry, &["location", "region"]);
+        job.job_type = child_text(entry, &["job_type", "type"]);
+        job.salary = child_text(entry, &["salary"]);
+        job.category = if atom {
+            entry.children().find(|c| c.is_element() && c.tag_name().name() == "category").and_then(|c| c.attribute("term").map(|t| t.to_string()))
+        } else {
+            child_text(entry, &["category"])
+        }.or_else(|| self.category.clone());
+        Some(job)
+    }
+}
+
+impl JobSource for RssSource {
+    fn name(&self) -> &str { &self.name }
+
+    fn location(&self) -> &str { &self.url }
+
+    fn parse(&self, body: &str) -> Result<Vec<Job>, String> {
+        let doc = Document::parse(body).map_err(|e| format!("Failed to parse {} feed: {}", self.name, e))?;
+        let root = doc.root_element();
+        let (atom, entries): (bool, Vec<Node>) = match root.tag_name().name() {
+            "feed" => (true, root.children().filter(|c| c.is_element() && c.tag_name().name() == "entry").collect()),
+            "rss" | "RDF" => (false, root.descendants().filter(|c| c.is_element() && c.tag_name().name() == "item").collect()),
+            other => return Err(format!("{} is not an RSS or Atom feed (root element <{}>)", self.name, other)),
+        };
+        Ok(entries.into_iter().filter_map(|e| self.entry_to_job(e, atom)).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sources::fetch;
+    use crate::utils::ids::stable_job_id;
+
+    fn source(name: &str, fixture: &str, category: Option<&str>) -> RssSource {
+        RssSource {
+            name: name.into(),
+            url: format!("{}/fixtures/sources/{}", env!("CARGO_MANIFEST_DIR"), fixture),
+            category: category.map(|c| c.to_string()),
+        }
+    }
+
+    #[actix_web::test]
+    async fn parses_rss_items() {
+        let jobs = fetch(&source("example-rss", "rss.xml", Some("Software Development"))).await.unwrap();
+        assert_eq!(jobs.len(), 2);
+
+        // "Company: Title" headline, guid as the id
+        let python = &jobs[0];
+        assert_eq!(python.id, stable_job_id("example-rss", "https://board.example.com/jobs/4411"));
+        assert_eq!((python.company.as_str(), python.title.as_str()), ("Acme Corp", "Senior Python Developer"));
+        assert_eq!(python.url, "https://board.example.com/jobs/4411-senior-python-developer");
+        assert_eq!(python.created_at, "2025-03-02T07:45:00+00:00");
+        assert_eq!(python.description, "<p>Django, Celery and AWS.</p>");
+        assert_eq!(python.job_type.as_deref(), Some("Full-Time"));
+        assert_eq!(python.category.as_deref(), Some("Programming"));
+        assert_eq!(python.location.as_deref(), Some("Anywhere in the World"));
+        assert!(python.location_info.as_ref().unwrap().worldwide);
+        assert_eq!(python.salary_range, None);
+
+        // dc:creator as the company, the link as the id, the source's category as the fallback
+        let devops = &jobs[1];
+        assert_eq!((devops.company.as_str(), devops.title.as_str()), ("CloudNine", "DevOps Engineer"));
+        assert_eq!(devops.id, stable_job_id("example-rss", "https://board.example.com/jobs/4420-devops-engineer"));
+        assert_eq!(devops.category.as_deref(), Some("Software Development"));
+        assert_eq!(devops.location_info, None);
+    }
+
+    #[actix_web::test]
+    async fn parses_atom_entries() {
+        let jobs = fetch(&source("example-atom", "atom.xml", None)).await.unwrap();
+        assert_eq!(jobs.len(), 1);
+        let ml = &jobs[0];
+        assert_eq!(ml.id, stable_job_id("example-atom", "urn:job:ml-7788"));
+        assert_eq!(ml.external_id.as_deref(), Some("urn:job:ml-7788"));
+        assert_eq!((ml.company.as_str(), ml.title.as_str()), ("Tensor Works", "Machine Learning Engineer"));
+        assert_eq!(ml.url, "https://atom.example.com/jobs/ml-7788");
+        assert_eq!(ml.created_at, "2025-03-02T16:20:00+00:00");
+        assert_eq!(ml.category.as_deref(), Some("Data"));
+        assert_eq!(ml.description, "PyTorch, model serving and feature stores.");
+    }
+
+    #[test]
+    fn rejects_documents_that_are_not_feeds() {
+        assert!(source("x", "rss.xml", None).parse("<html><body/></html>").is_err());
+        assert!(source("x", "rss.xml", None).parse("not xml").is_err());
+    }
+}
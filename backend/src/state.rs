@@ -1,63 +1,149 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::sync::Mutex;
 use std::fs;
 use actix_web::web::Data;
 use chrono::Utc;
-use crate::models::{User, Job};
+use serde::{de::DeserializeOwned, Serialize};
+use crate::models::{Application, User, Job, JobAlert, Notification, PasswordResetRecord, RefreshTokenRecord, ResumeRecord, Role, SyncReport, UserFeedback};
+use crate::storage::Storage;
+use crate::utils::search::SearchIndex;
+use crate::utils::auth::listed_admin;
+use crate::utils::google::GoogleVerifier;
+use crate::utils::mail::Mailer;
+
+/// In-process usage counters since the server started, surfaced by /analytics/metrics.
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct UsageMetrics {
+    pub recommendation_requests: u64,
+    pub recommendations_served: u64,
+    pub resume_ml_matches: u64,
+    pub resume_fallback_matches: u64,
+    pub job_ml_analyses: u64,
+    pub job_fallback_analyses: u64,
+}
+
+/// Bookkeeping for catalog syncs, whether run by the background worker or
+/// `POST /jobs/sync`; surfaced by /jobs/sync/status.
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct IngestionStatus {
+    pub worker_enabled: bool,
+    pub interval_secs: u64,
+    pub running: bool,
+    pub last_trigger: Option<String>,
+    pub last_started_at: Option<String>,
+    pub last_finished_at: Option<String>,
+    pub last_duration_ms: Option<u64>,
+    pub last_success_at: Option<String>,
+    pub last_report: Option<SyncReport>,
+    pub last_error: Option<String>,
+    pub consecutive_failures: u32,
+    pub total_runs: u64,
+    pub total_failures: u64,
+    pub next_run_at: Option<String>,
+}
 
 pub struct AppState {
     pub users: Mutex<Vec<User>>,                   // simple JSON-backed store
-    pub refresh_tokens: Mutex<HashMap<String, String>>, // refresh_token -> user_id
+    pub refresh_tokens: Mutex<HashMap<String, RefreshTokenRecord>>, // keyed by sha256(refresh_token)
     pub jobs_cache: Mutex<Vec<Job>>,               // cached jobs from Remotive
+    pub search_index: Mutex<SearchIndex>,          // rebuilt from jobs_cache after every sync
+    pub unsaved_jobs: Mutex<HashSet<String>>,      // changed by a sync whose save failed; retried next sync
+    pub applications: Mutex<Vec<Application>>,     // JSON-backed, alongside users
+    pub feedback: Mutex<Vec<UserFeedback>>,
+    pub notifications: Mutex<Vec<Notification>>,
+    pub job_alerts: Mutex<Vec<JobAlert>>,
+    pub password_resets: Mutex<Vec<PasswordResetRecord>>,
     pub uploads_dir: PathBuf,
     pub data_dir: PathBuf,
     pub jwt_secret: String,
+    pub google: GoogleVerifier,
+    pub mailer: Mailer,
     pub idf: Mutex<HashMap<String, f64>>,         // optional IDF model
+    pub metrics: Mutex<UsageMetrics>,
+    pub ingestion: Mutex<IngestionStatus>,
+    pub started_at: String,
+    pub storage: Storage,                          // users, refresh tokens, jobs, resumes
 }
 
 pub fn data_dir() -> PathBuf {
     PathBuf::from("./data")
 }
 
-pub fn users_db_path() -> PathBuf { data_dir().join("users.json") }
-pub fn jobs_cache_path() -> PathBuf { data_dir().join("jobs_cache.json") }
+pub fn users_db_path(dir: &Path) -> PathBuf { dir.join("users.json") }
+pub fn jobs_cache_path(dir: &Path) -> PathBuf { dir.join("jobs_cache.json") }
+pub fn applications_db_path() -> PathBuf { data_dir().join("applications.json") }
+pub fn feedback_db_path() -> PathBuf { data_dir().join("feedback.json") }
+pub fn notifications_db_path() -> PathBuf { data_dir().join("notifications.json") }
+pub fn job_alerts_db_path() -> PathBuf { data_dir().join("job_alerts.json") }
+pub fn refresh_tokens_db_path(dir: &Path) -> PathBuf { dir.join("refresh_tokens.json") }
+pub fn resumes_db_path(dir: &Path) -> PathBuf { dir.join("resumes.json") }
+pub fn password_resets_db_path(dir: &Path) -> PathBuf { dir.join("password_resets.json") }
 
 pub fn ensure_dirs(state: &AppState) {
     if !state.data_dir.exists() { let _ = fs::create_dir_all(&state.data_dir); }
     if !state.uploads_dir.exists() { let _ = fs::create_dir_all(&state.uploads_dir); }
 }
 
-pub fn load_users() -> Vec<User> {
-    let path = users_db_path();
+fn load_json_vec<T: DeserializeOwned>(path: PathBuf) -> Vec<T> {
     if let Ok(content) = fs::read_to_string(path) {
-        if let Ok(users) = serde_json::from_str::<Vec<User>>(&content) { return users; }
+        if let Ok(items) = serde_json::from_str::<Vec<T>>(&content) { return items; }
     }
     vec![]
 }
 
-pub fn save_users(users: &Vec<User>) -> std::io::Result<()> {
-    let path = users_db_path();
+fn save_json_vec<T: Serialize>(path: PathBuf, items: &[T]) -> std::io::Result<()> {
     if let Some(parent) = path.parent() { fs::create_dir_all(parent)?; }
-    let content = serde_json::to_string_pretty(users).unwrap_or_else(|_| "[]".to_string());
+    let content = serde_json::to_string_pretty(items).unwrap_or_else(|_| "[]".to_string());
     fs::write(path, content)
 }
 
-pub fn load_jobs_cache() -> Vec<Job> {
-    let path = jobs_cache_path();
-    if let Ok(content) = fs::read_to_string(path) {
-        if let Ok(jobs) = serde_json::from_str::<Vec<Job>>(&content) { return jobs; }
-    }
-    vec![]
+pub fn load_users(dir: &Path) -> Vec<User> { load_json_vec(users_db_path(dir)) }
+
+pub fn save_users(dir: &Path, users: &[User]) -> std::io::Result<()> { save_json_vec(users_db_path(dir), users) }
+
+pub fn load_jobs_cache(dir: &Path) -> Vec<Job> { load_json_vec(jobs_cache_path(dir)) }
+
+pub fn save_jobs_cache(dir: &Path, jobs: &[Job]) -> std::io::Result<()> { save_json_vec(jobs_cache_path(dir), jobs) }
+
+pub fn load_refresh_tokens(dir: &Path) -> HashMap<String, RefreshTokenRecord> {
+    fs::read_to_string(refresh_tokens_db_path(dir)).ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
 }
 
-pub fn save_jobs_cache(jobs: &Vec<Job>) -> std::io::Result<()> {
-    let path = jobs_cache_path();
+pub fn save_refresh_tokens(dir: &Path, tokens: &HashMap<String, RefreshTokenRecord>) -> std::io::Result<()> {
+    let path = refresh_tokens_db_path(dir);
     if let Some(parent) = path.parent() { fs::create_dir_all(parent)?; }
-    let content = serde_json::to_string_pretty(jobs).unwrap_or_else(|_| "[]".to_string());
-    fs::write(path, content)
+    fs::write(path, serde_json::to_string_pretty(tokens).unwrap_or_else(|_| "{}".to_string()))
+}
+
+pub fn record_resume_json(dir: &Path, resume: ResumeRecord) -> std::io::Result<()> {
+    let mut resumes: Vec<ResumeRecord> = load_json_vec(resumes_db_path(dir));
+    resumes.push(resume);
+    save_json_vec(resumes_db_path(dir), &resumes)
 }
 
+pub fn load_applications() -> Vec<Application> { load_json_vec(applications_db_path()) }
+
+pub fn save_applications(applications: &[Application]) -> std::io::Result<()> { save_json_vec(applications_db_path(), applications) }
+
+pub fn load_feedback() -> Vec<UserFeedback> { load_json_vec(feedback_db_path()) }
+
+pub fn save_feedback(feedback: &[UserFeedback]) -> std::io::Result<()> { save_json_vec(feedback_db_path(), feedback) }
+
+pub fn load_notifications() -> Vec<Notification> { load_json_vec(notifications_db_path()) }
+
+pub fn save_notifications(notifications: &[Notification]) -> std::io::Result<()> { save_json_vec(notifications_db_path(), notifications) }
+
+pub fn load_job_alerts() -> Vec<JobAlert> { load_json_vec(job_alerts_db_path()) }
+
+pub fn save_job_alerts(alerts: &[JobAlert]) -> std::io::Result<()> { save_json_vec(job_alerts_db_path(), alerts) }
+
+pub fn load_password_resets(dir: &Path) -> Vec<PasswordResetRecord> { load_json_vec(password_resets_db_path(dir)) }
+
+pub fn save_password_resets(dir: &Path, resets: &[PasswordResetRecord]) -> std::io::Result<()> { save_json_vec(password_resets_db_path(dir), resets) }
+
 pub fn load_idf_model() -> HashMap<String, f64> {
     let csv_path = Path::new("./dataset/job_descriptions.csv");
     if !csv_path.exists() { return HashMap::new(); }
@@ -89,17 +175,78 @@ pub fn load_idf_model() -> HashMap<String, f64> {
     idf
 }
 
-pub fn init_state(jwt_secret: String) -> Data<AppState> {
+pub async fn init_state(jwt_secret: String, storage: Storage) -> Result<Data<AppState>, String> {
+    let mut users = storage.load_users().await?;
+    // Accounts listed in ADMIN_EMAILS are promoted on startup; demotion is done through the API
+    let mut promoted = vec![];
+    for u in users.iter_mut().filter(|u| u.role != Role::Admin && listed_admin(&u.email)) {
+        u.role = Role::Admin;
+        promoted.push(u.id.clone());
+    }
+    if !promoted.is_empty() {
+        println!("🔑 Promoted {} account(s) from ADMIN_EMAILS to admin", promoted.len());
+        let ids: Vec<&str> = promoted.iter().map(|id| id.as_str()).collect();
+        storage.save_users(&users, &ids).await.map_err(|e| format!("Failed to save users: {}", e))?;
+    }
+    let refresh_tokens = storage.load_refresh_tokens().await?;
+    let jobs = storage.load_jobs().await?;
+    let password_resets = storage.load_password_resets().await?;
+    let search_index = SearchIndex::build(&jobs);
+    println!("🗄️ Storage: {} ({} users, {} jobs)", storage.name(), users.len(), jobs.len());
     let state = AppState {
-        users: Mutex::new(load_users()),
-        refresh_tokens: Mutex::new(HashMap::new()),
-        jobs_cache: Mutex::new(load_jobs_cache()),
+        users: Mutex::new(users),
+        refresh_tokens: Mutex::new(refresh_tokens),
+        jobs_cache: Mutex::new(jobs),
+        search_index: Mutex::new(search_index),
+        unsaved_jobs: Mutex::new(HashSet::new()),
+        applications: Mutex::new(load_applications()),
+        feedback: Mutex::new(load_feedback()),
+        notifications: Mutex::new(load_notifications()),
+        job_alerts: Mutex::new(load_job_alerts()),
+        password_resets: Mutex::new(password_resets),
         uploads_dir: PathBuf::from("./uploads/user_resumes"),
         data_dir: data_dir(),
         jwt_secret,
+        google: GoogleVerifier::from_env(),
+        mailer: Mailer::from_env(),
         idf: Mutex::new(load_idf_model()),
+        metrics: Mutex::new(UsageMetrics::default()),
+        ingestion: Mutex::new(IngestionStatus::default()),
+        started_at: Utc::now().to_rfc3339(),
+        storage,
     };
     ensure_dirs(&state);
-    Data::new(state)
+    Ok(Data::new(state))
 }
 
+
+/// An empty JSON-backed state in a fresh temp directory, with mail spooled under it
+/// and Google sign-in checked against the fixture keys. Remove `data_dir` when done.
+#[cfg(test)]
+pub fn scratch_state() -> Data<AppState> {
+    let dir = std::env::temp_dir().join(format!("joblens_test_{}", uuid::Uuid::new_v4().simple()));
+    let state = AppState {
+        users: Mutex::new(vec![]),
+        refresh_tokens: Mutex::new(HashMap::new()),
+        jobs_cache: Mutex::new(vec![]),
+        search_index: Mutex::new(SearchIndex::build(&[])),
+        unsaved_jobs: Mutex::new(HashSet::new()),
+        applications: Mutex::new(vec![]),
+        feedback: Mutex::new(vec![]),
+        notifications: Mutex::new(vec![]),
+        job_alerts: Mutex::new(vec![]),
+        password_resets: Mutex::new(vec![]),
+        uploads_dir: dir.join("uploads"),
+        data_dir: dir.clone(),
+        jwt_secret: "test-secret".into(),
+        google: crate::utils::google::testing::verifier(),
+        mailer: Mailer::Spool { dir: dir.join("mail"), from: "JobLens <no-reply@joblens.app>".into() },
+        idf: Mutex::new(HashMap::new()),
+        metrics: Mutex::new(UsageMetrics::default()),
+        ingestion: Mutex::new(IngestionStatus::default()),
+        started_at: Utc::now().to_rfc3339(),
+        storage: Storage::Json(dir),
+    };
+    ensure_dirs(&state);
+    Data::new(state)
+}
@@ -0,0 +1,133 @@
+mod postgres;
+
+use std::collections::{HashMap, HashSet};
+use std::env;
+use std::path::PathBuf;
+
+use actix_web::HttpResponse;
+use futures::future::{self, BoxFuture};
+
+use crate::models::{ApiResponse, Job, PasswordResetRecord, RefreshTokenRecord, ResumeRecord, User};
+use crate::state::{
+    data_dir, load_jobs_cache, load_password_resets, load_refresh_tokens, load_users, record_resume_json, save_jobs_cache,
+    save_password_resets, save_refresh_tokens, save_users,
+};
+use postgres::{PgStore, Write};
+
+/// Resolves once a save is persisted. Saves are started while the state lock is held,
+/// so they reach storage in order, and awaited after the lock is released.
+pub type PendingWrite = BoxFuture<'static, std::io::Result<()>>;
+
+fn finished(result: std::io::Result<()>) -> PendingWrite {
+    Box::pin(future::ready(result))
+}
+
+/// The response for a change that was made in memory but couldn't be persisted.
+pub fn save_failed(err: std::io::Error) -> HttpResponse {
+    println!("❌ Failed to persist changes: {}", err);
+    HttpResponse::InternalServerError().json(ApiResponse::<serde_json::Value> { success: false, data: None, message: "Failed to save changes".into() })
+}
+
+/// Where users, refresh tokens, jobs, resume uploads and password reset codes are persisted: Postgres when
+/// `DATABASE_URL` is set, JSON files in the given directory (`./data`) otherwise. Either way the
+/// working set lives in `AppState`. Saves get the whole collection plus the keys that
+/// changed: JSON rewrites the file, Postgres writes only those rows.
+pub enum Storage {
+    Json(PathBuf),
+    Postgres(PgStore),
+}
+
+impl Storage {
+    /// Connect and migrate when `DATABASE_URL` is set. A database that is configured
+    /// but unreachable is an error rather than a silent fallback to JSON.
+    pub async fn from_env() -> Result<Storage, String> {
+        match env::var("DATABASE_URL").ok().filter(|u| !u.trim().is_empty()) {
+            Some(url) => Ok(Storage::Postgres(PgStore::connect(&url).await?)),
+            None => Ok(Storage::Json(data_dir())),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Storage::Json(_) => "json",
+            Storage::Postgres(_) => "postgres",
+        }
+    }
+
+    pub async fn load_users(&self) -> Result<Vec<User>, String> {
+        match self {
+            Storage::Json(dir) => Ok(load_users(dir)),
+            Storage::Postgres(pg) => pg.load_users().await.map_err(|e| format!("Failed to load users: {}", e)),
+        }
+    }
+
+    pub async fn load_refresh_tokens(&self) -> Result<HashMap<String, RefreshTokenRecord>, String> {
+        match self {
+            Storage::Json(dir) => Ok(load_refresh_tokens(dir)),
+            Storage::Postgres(pg) => pg.load_refresh_tokens().await.map_err(|e| format!("Failed to load refresh tokens: {}", e)),
+        }
+    }
+
+    pub async fn load_jobs(&self) -> Result<Vec<Job>, String> {
+        match self {
+            Storage::Json(dir) => Ok(load_jobs_cache(dir)),
+            Storage::Postgres(pg) => pg.load_jobs().await.map_err(|e| format!("Failed to load jobs: {}", e)),
+        }
+    }
+
+    pub async fn load_password_resets(&self) -> Result<Vec<PasswordResetRecord>, String> {
+        match self {
+            Storage::Json(dir) => Ok(load_password_resets(dir)),
+            Storage::Postgres(pg) => pg.load_password_resets().await.map_err(|e| format!("Failed to load password resets: {}", e)),
+        }
+    }
+
+    pub fn save_users(&self, users: &[User], changed: &[&str]) -> PendingWrite {
+        match self {
+            Storage::Json(dir) => finished(save_users(dir, users)),
+            Storage::Postgres(pg) => pg.submit(Write::Users(users.iter().filter(|u| changed.contains(&u.id.as_str())).cloned().collect())),
+        }
+    }
+
+    /// `tokens` is keyed by refresh token hash; a changed hash that is no longer in
+    /// `tokens` is deleted.
+    pub fn save_refresh_tokens(&self, tokens: &HashMap<String, RefreshTokenRecord>, changed: &[String]) -> PendingWrite {
+        match self {
+            Storage::Json(dir) => finished(save_refresh_tokens(dir, tokens)),
+            Storage::Postgres(pg) => {
+                let (kept, removed): (Vec<&String>, Vec<&String>) = changed.iter().partition(|h| tokens.contains_key(*h));
+                pg.submit(Write::RefreshTokens {
+                    upsert: kept.into_iter().map(|h| (h.clone(), tokens[h].clone())).collect(),
+                    delete: removed.into_iter().cloned().collect(),
+                })
+            }
+        }
+    }
+
+    pub fn save_jobs(&self, jobs: &[Job], changed: &HashSet<String>) -> PendingWrite {
+        match self {
+            Storage::Json(dir) => finished(save_jobs_cache(dir, jobs)),
+            Storage::Postgres(pg) => pg.submit(Write::Jobs(
+                jobs.iter().enumerate().filter(|(_, j)| changed.contains(&j.id)).map(|(i, j)| (i as i64, j.clone())).collect(),
+            )),
+        }
+    }
+
+    pub fn record_resume(&self, resume: ResumeRecord) -> PendingWrite {
+        match self {
+            Storage::Json(dir) => finished(record_resume_json(dir, resume)),
+            Storage::Postgres(pg) => pg.submit(Write::Resume(resume)),
+        }
+    }
+
+    /// `changed` holds token hashes; one that is no longer in `resets` is deleted.
+    pub fn save_password_resets(&self, resets: &[PasswordResetRecord], changed: &[String]) -> PendingWrite {
+        match self {
+            Storage::Json(dir) => finished(save_password_resets(dir, resets)),
+            Storage::Postgres(pg) => pg.submit(Write::PasswordResets {
+                upsert: resets.iter().filter(|r| changed.contains(&r.token_hash)).cloned().collect(),
+                delete: changed.iter().filter(|h| !resets.iter().any(|r| &r.token_hash == *h)).cloned().collect(),
+            }),
+        }
+    }
+}
@@ -0,0 +1,576 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use futures::channel::oneshot;
+use sqlx::postgres::{PgPool, PgPoolOptions, PgRow};
+use sqlx::types::Json;
+use sqlx::{Executor, Row};
+use tokio::sync::mpsc::{self, error::TrySendError};
+
+use crate::models::{Job, PasswordResetRecord, RefreshTokenRecord, ResumeRecord, Role, User};
+use super::PendingWrite;
+
+// Applied in order and recorded in schema_migrations; never edit one that has shipped, add a new one
+const MIGRATIONS: [(i64, &str, &str); 9] = [
+    (1, "create_users", include_str!("../../migrations/0001_create_users.sql")),
+    (2, "create_refresh_tokens", include_str!("../../migrations/0002_create_refresh_tokens.sql")),
+    (3, "create_jobs", include_str!("../../migrations/0003_create_jobs.sql")),
+    (4, "create_resumes", include_str!("../../migrations/0004_create_resumes.sql")),
+    (5, "add_user_roles", include_str!("../../migrations/0005_add_user_roles.sql")),
+    (6, "refresh_token_rotation", include_str!("../../migrations/0006_refresh_token_rotation.sql")),
+    (7, "add_user_google_sub", include_str!("../../migrations/0007_add_user_google_sub.sql")),
+    (8, "create_password_resets", include_str!("../../migrations/0008_create_password_resets.sql")),
+    (9, "password_reset_attempts", include_str!("../../migrations/0009_password_reset_attempts.sql")),
+];
+
+// Saves beyond this many unapplied writes fail instead of piling up in memory
+const WRITE_QUEUE_LIMIT: usize = 1024;
+
+/// The rows one save changes; nothing outside them is touched.
+pub(super) enum Write {
+    Users(Vec<User>),
+    RefreshTokens { upsert: Vec<(String, RefreshTokenRecord)>, delete: Vec<String> },
+    // With each job's position in the catalog
+    Jobs(Vec<(i64, Job)>),
+    Resume(ResumeRecord),
+    PasswordResets { upsert: Vec<PasswordResetRecord>, delete: Vec<String> },
+}
+
+type Reply = oneshot::Sender<Result<(), sqlx::Error>>;
+
+/// Postgres connection plus the queue of pending writes. Handlers save while holding
+/// the state locks, so writes are queued and applied in order by a single task, which
+/// reports each outcome back to the caller awaiting it.
+pub struct PgStore {
+    pool: PgPool,
+    writer: mpsc::Sender<(Write, Reply)>,
+}
+
+impl PgStore {
+    pub async fn connect(url: &str) -> Result<PgStore, String> {
+        let pool = PgPoolOptions::new()
+            .max_connections(5)
+            .connect(url)
+            .await
+            .map_err(|e| format!("Failed to connect to Postgres: {}", e))?;
+        migrate(&pool).await.map_err(|e| format!("Failed to run migrations: {}", e))?;
+
+        let (writer, queue) = mpsc::channel(WRITE_QUEUE_LIMIT);
+        actix_web::rt::spawn(run_writer(pool.clone(), queue));
+        Ok(PgStore { pool, writer })
+    }
+
+    pub(super) fn submit(&self, write: Write) -> PendingWrite {
+        let (reply, outcome) = oneshot::channel();
+        if let Err(e) = self.writer.try_send((write, reply)) {
+            let reason = match e {
+                TrySendError::Full(_) => "Postgres write queue is full",
+                TrySendError::Closed(_) => "Postgres writer has stopped",
+            };
+            return super::finished(Err(std::io::Error::other(reason)));
+        }
+        Box::pin(async move {
+            match outcome.await {
+                Ok(result) => result.map_err(|e| std::io::Error::other(format!("Postgres write failed: {}", e))),
+                Err(_) => Err(std::io::Error::other("Postgres writer has stopped")),
+            }
+        })
+    }
+
+    pub async fn load_users(&self) -> Result<Vec<User>, sqlx::Error> {
+        let rows = sqlx::query("SELECT * FROM users ORDER BY created_at, id").fetch_all(&self.pool).await?;
+        rows.iter().map(user_from_row).collect()
+    }
+
+    pub async fn load_refresh_tokens(&self) -> Result<HashMap<String, RefreshTokenRecord>, sqlx::Error> {
+        let rows = sqlx::query("SELECT * FROM refresh_tokens").fetch_all(&self.pool).await?;
+        rows.iter().map(|r| Ok((r.try_get("token_hash")?, refresh_token_from_row(r)?))).collect()
+    }
+
+    pub async fn load_jobs(&self) -> Result<Vec<Job>, sqlx::Error> {
+        let rows = sqlx::query("SELECT * FROM jobs ORDER BY position").fetch_all(&self.pool).await?;
+        rows.iter().map(job_from_row).collect()
+    }
+
+    pub async fn load_password_resets(&self) -> Result<Vec<PasswordResetRecord>, sqlx::Error> {
+        let rows = sqlx::query("SELECT * FROM password_resets ORDER BY created_at").fetch_all(&self.pool).await?;
+        rows.iter().map(password_reset_from_row).collect()
+    }
+}
+
+async fn migrate(pool: &PgPool) -> Result<(), sqlx::Error> {
+    pool.execute(
+        "CREATE TABLE IF NOT EXISTS schema_migrations (
+            version BIGINT PRIMARY KEY,
+            name TEXT NOT NULL,
+            applied_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+        )",
+    ).await?;
+    let applied: Vec<i64> = sqlx::query_scalar("SELECT version FROM schema_migrations").fetch_all(pool).await?;
+
+    for (version, name, sql) in MIGRATIONS.iter() {
+        if applied.contains(version) { continue; }
+        let mut tx = pool.begin().await?;
+        (&mut tx).execute(*sql).await?;
+        sqlx::query("INSERT INTO schema_migrations (version, name) VALUES ($1, $2)")
+            .bind(version)
+            .bind(name)
+            .execute(&mut tx)
+            .await?;
+        tx.commit().await?;
+        println!("🗄️ Applied migration {:04}_{}", version, name);
+    }
+    Ok(())
+}
+
+/// Apply queued writes one at a time, in the order they were submitted.
+async fn run_writer(pool: PgPool, mut queue: mpsc::Receiver<(Write, Reply)>) {
+    while let Some((write, reply)) = queue.recv().await {
+        let result = match &write {
+            Write::Users(users) => write_users(&pool, users).await,
+            Write::RefreshTokens { upsert, delete } => write_refresh_tokens(&pool, upsert, delete).await,
+            Write::Jobs(jobs) => write_jobs(&pool, jobs).await,
+            Write::Resume(resume) => write_resume(&pool, resume).await,
+            Write::PasswordResets { upsert, delete } => write_password_resets(&pool, upsert, delete).await,
+        };
+        if let Err(e) = &result {
+            println!("⚠️ Failed to persist to Postgres: {}", e);
+        }
+        // The caller may have given up waiting; the write stands either way
+        let _ = reply.send(result);
+    }
+}
+
+fn timestamp(value: &str) -> DateTime<Utc> {
+    DateTime::parse_from_rfc3339(value).map(|t| t.with_timezone(&Utc)).unwrap_or_else(|_| Utc::now())
+}
+
+async fn write_users(pool: &PgPool, users: &[User]) -> Result<(), sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    for u in users {
+        sqlx::query(
+            "INSERT INTO users (id, email, password_hash, first_name, last_name, skills, experience_years, location, resume_filename, created_at, updated_at, role, google_sub)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+             ON CONFLICT (id) DO UPDATE SET
+                email = EXCLUDED.email, password_hash = EXCLUDED.password_hash, first_name = EXCLUDED.first_name,
+                last_name = EXCLUDED.last_name, skills = EXCLUDED.skills, experience_years = EXCLUDED.experience_years,
+                location = EXCLUDED.location, resume_filename = EXCLUDED.resume_filename, updated_at = EXCLUDED.updated_at,
+                role = EXCLUDED.role, google_sub = EXCLUDED.google_sub",
+        )
+        .bind(&u.id)
+        .bind(&u.email)
+        .bind(&u.password_hash)
+        .bind(&u.first_name)
+        .bind(&u.last_name)
+        .bind(&u.skills)
+        .bind(u.experience_years)
+        .bind(&u.location)
+        .bind(&u.resume_filename)
+        .bind(timestamp(&u.created_at))
+        .bind(timestamp(&u.updated_at))
+        .bind(u.role.as_str())
+        .bind(&u.google_sub)
+        .execute(&mut tx)
+        .await?;
+    }
+    tx.commit().await
+}
+
+fn user_from_row(row: &PgRow) -> Result<User, sqlx::Error> {
+    Ok(User {
+        id: row.try_get("id")?,
+        email: row.try_get("email")?,
+        password_hash: row.try_get("password_hash")?,
+        first_name: row.try_get("first_name")?,
+        last_name: row.try_get("last_name")?,
+        skills: row.try_get("skills")?,
+        experience_years: row.try_get("experience_years")?,
+        location: row.try_get("location")?,
+        created_at: row.try_get::<DateTime<Utc>, _>("created_at")?.to_rfc3339(),
+        updated_at: row.try_get::<DateTime<Utc>, _>("updated_at")?.to_rfc3339(),
+        resume_filename: row.try_get("resume_filename")?,
+        role: Role::parse(row.try_get("role")?).unwrap_or_default(),
+        google_sub: row.try_get("google_sub")?,
+    })
+}
+
+async fn write_refresh_tokens(pool: &PgPool, upsert: &[(String, RefreshTokenRecord)], delete: &[String]) -> Result<(), sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    if !delete.is_empty() {
+        sqlx::query("DELETE FROM refresh_tokens WHERE token_hash = ANY($1)").bind(delete).execute(&mut tx).await?;
+    }
+    for (hash, t) in upsert {
+        sqlx::query(
+            "INSERT INTO refresh_tokens (token_hash, user_id, family_id, created_at, expires_at, revoked_at, replaced_by)
+             VALUES ($1, $2, $3, $4, $5, $6, $7)
+             ON CONFLICT (token_hash) DO UPDATE SET revoked_at = EXCLUDED.revoked_at, replaced_by = EXCLUDED.replaced_by",
+        )
+        .bind(hash)
+        .bind(&t.user_id)
+        .bind(&t.family_id)
+        .bind(timestamp(&t.issued_at))
+        .bind(timestamp(&t.expires_at))
+        .bind(t.revoked_at.as_deref().map(timestamp))
+        .bind(&t.replaced_by)
+        .execute(&mut tx)
+        .await?;
+    }
+    tx.commit().await
+}
+
+fn refresh_token_from_row(row: &PgRow) -> Result<RefreshTokenRecord, sqlx::Error> {
+    Ok(RefreshTokenRecord {
+        user_id: row.try_get("user_id")?,
+        family_id: row.try_get("family_id")?,
+        issued_at: row.try_get::<DateTime<Utc>, _>("created_at")?.to_rfc3339(),
+        expires_at: row.try_get::<DateTime<Utc>, _>("expires_at")?.to_rfc3339(),
+        revoked_at: row.try_get::<Option<DateTime<Utc>>, _>("revoked_at")?.map(|t| t.to_rfc3339()),
+        replaced_by: row.try_get("replaced_by")?,
+    })
+}
+
+async fn write_jobs(pool: &PgPool, jobs: &[(i64, Job)]) -> Result<(), sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    for (position, j) in jobs {
+        sqlx::query(
+            "INSERT INTO jobs (id, source, external_id, title, company, company_logo, location, location_info, url, description,
+                               job_type, salary, salary_range, category, created_at, expired_at, duplicate_of, source_urls, position)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19)
+             ON CONFLICT (id) DO UPDATE SET
+                source = EXCLUDED.source, external_id = EXCLUDED.external_id, title = EXCLUDED.title, company = EXCLUDED.company,
+                company_logo = EXCLUDED.company_logo, location = EXCLUDED.location, location_info = EXCLUDED.location_info,
+                url = EXCLUDED.url, description = EXCLUDED.description, job_type = EXCLUDED.job_type, salary = EXCLUDED.salary,
+                salary_range = EXCLUDED.salary_range, category = EXCLUDED.category, created_at = EXCLUDED.created_at,
+                expired_at = EXCLUDED.expired_at, duplicate_of = EXCLUDED.duplicate_of, source_urls = EXCLUDED.source_urls,
+                position = EXCLUDED.position",
+        )
+        .bind(&j.id)
+        .bind(&j.source)
+        .bind(&j.external_id)
+        .bind(&j.title)
+        .bind(&j.company)
+        .bind(&j.company_logo)
+        .bind(&j.location)
+        .bind(j.location_info.as_ref().map(Json))
+        .bind(&j.url)
+        .bind(&j.description)
+        .bind(&j.job_type)
+        .bind(&j.salary)
+        .bind(j.salary_range.as_ref().map(Json))
+        .bind(&j.category)
+        .bind(timestamp(&j.created_at))
+        .bind(j.expired_at.as_deref().map(timestamp))
+        .bind(&j.duplicate_of)
+        .bind(Json(&j.source_urls))
+        .bind(position)
+        .execute(&mut tx)
+        .await?;
+    }
+    tx.commit().await
+}
+
+fn job_from_row(row: &PgRow) -> Result<Job, sqlx::Error> {
+    Ok(Job {
+        id: row.try_get("id")?,
+        title: row.try_get("title")?,
+        company: row.try_get("company")?,
+        company_logo: row.try_get("company_logo")?,
+        location: row.try_get("location")?,
+        location_info: row.try_get::<Option<Json<_>>, _>("location_info")?.map(|j| j.0),
+        url: row.try_get("url")?,
+        description: row.try_get("description")?,
+        created_at: row.try_get::<DateTime<Utc>, _>("created_at")?.to_rfc3339(),
+        external_id: row.try_get("external_id")?,
+        source: row.try_get("source")?,
+        job_type: row.try_get("job_type")?,
+        salary: row.try_get("salary")?,
+        salary_range: row.try_get::<Option<Json<_>>, _>("salary_range")?.map(|j| j.0),
+        category: row.try_get("category")?,
+        expired_at: row.try_get::<Option<DateTime<Utc>>, _>("expired_at")?.map(|t| t.to_rfc3339()),
+        duplicate_of: row.try_get("duplicate_of")?,
+        source_urls: row.try_get::<Json<_>, _>("source_urls")?.0,
+    })
+}
+
+async fn write_resume(pool: &PgPool, r: &ResumeRecord) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "INSERT INTO resumes (id, user_id, application_id, version, filename, original_filename, content_type, size_bytes, uploaded_at)
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+         ON CONFLICT (id) DO NOTHING",
+    )
+    .bind(&r.id)
+    .bind(&r.user_id)
+    .bind(&r.application_id)
+    .bind(r.version.map(|v| v as i32))
+    .bind(&r.filename)
+    .bind(&r.original_filename)
+    .bind(&r.content_type)
+    .bind(r.size_bytes as i64)
+    .bind(timestamp(&r.uploaded_at))
+    .execute(pool)
+    .await
+    .map(|_| ())
+}
+
+async fn write_password_resets(pool: &PgPool, upsert: &[PasswordResetRecord], delete: &[String]) -> Result<(), sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    if !delete.is_empty() {
+        sqlx::query("DELETE FROM password_resets WHERE token_hash = ANY($1)").bind(delete).execute(&mut tx).await?;
+    }
+    for r in upsert {
+        sqlx::query(
+            "INSERT INTO password_resets (token_hash, user_id, created_at, expires_at, used_at, attempts)
+             VALUES ($1, $2, $3, $4, $5, $6)
+             ON CONFLICT (token_hash) DO UPDATE SET used_at = EXCLUDED.used_at, attempts = EXCLUDED.attempts",
+        )
+        .bind(&r.token_hash)
+        .bind(&r.user_id)
+        .bind(timestamp(&r.created_at))
+        .bind(timestamp(&r.expires_at))
+        .bind(r.used_at.as_deref().map(timestamp))
+        .bind(r.attempts as i32)
+        .execute(&mut tx)
+        .await?;
+    }
+    tx.commit().await
+}
+
+fn password_reset_from_row(row: &PgRow) -> Result<PasswordResetRecord, sqlx::Error> {
+    Ok(PasswordResetRecord {
+        token_hash: row.try_get("token_hash")?,
+        user_id: row.try_get("user_id")?,
+        created_at: row.try_get::<DateTime<Utc>, _>("created_at")?.to_rfc3339(),
+        expires_at: row.try_get::<DateTime<Utc>, _>("expires_at")?.to_rfc3339(),
+        used_at: row.try_get::<Option<DateTime<Utc>>, _>("used_at")?.map(|t| t.to_rfc3339()),
+        attempts: row.try_get::<i32, _>("attempts")? as u32,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{HashMap, HashSet};
+
+    use super::*;
+    use crate::models::{JobLocation, SalaryPeriod, SalaryRange, SourceUrl};
+    use crate::sources::new_job;
+    use crate::storage::Storage;
+
+    const T0: &str = "2024-05-01T10:00:00+00:00";
+    const T1: &str = "2024-06-01T10:00:00+00:00";
+
+    /// A throwaway database next to the one `TEST_DATABASE_URL` (or `DATABASE_URL`)
+    /// points at, migrated from scratch. The tests using it are ignored by default and
+    /// fail rather than pass when run without a database.
+    struct Scratch {
+        admin_url: String,
+        name: String,
+        storage: Storage,
+    }
+
+    impl Scratch {
+        async fn create() -> Scratch {
+            let admin_url = std::env::var("TEST_DATABASE_URL").or_else(|_| std::env::var("DATABASE_URL"))
+                .expect("set TEST_DATABASE_URL to run the Postgres tests");
+            let name = format!("joblens_test_{}", uuid::Uuid::new_v4().simple());
+            let admin = PgPoolOptions::new().max_connections(1).connect(&admin_url).await.expect("connect to test database");
+            admin.execute(format!("CREATE DATABASE {}", name).as_str()).await.expect("create scratch database");
+            admin.close().await;
+            let store = PgStore::connect(&Scratch::url(&admin_url, &name)).await.expect("migrate scratch database");
+            Scratch { admin_url, name, storage: Storage::Postgres(store) }
+        }
+
+        fn url(admin_url: &str, name: &str) -> String {
+            let (base, query) = admin_url.split_once('?').map(|(b, q)| (b, format!("?{}", q))).unwrap_or((admin_url, String::new()));
+            let base = base.rsplit_once('/').map(|(host, _)| host).unwrap_or(base);
+            format!("{}/{}{}", base, name, query)
+        }
+
+        fn pg(&self) -> &PgStore {
+            match &self.storage {
+                Storage::Postgres(pg) => pg,
+                Storage::Json(_) => unreachable!(),
+            }
+        }
+
+        async fn drop_database(self) {
+            self.pg().pool.close().await;
+            let admin = PgPoolOptions::new().max_connections(1).connect(&self.admin_url).await.unwrap();
+            admin.execute(format!("DROP DATABASE {} WITH (FORCE)", self.name).as_str()).await.unwrap();
+        }
+    }
+
+    fn user(id: &str, email: &str) -> User {
+        User {
+            id: id.into(),
+            email: email.into(),
+            password_hash: Some("$2b$12$hash".into()),
+            first_name: "Ada".into(),
+            last_name: "Lovelace".into(),
+            skills: vec!["rust".into(), "sql".into()],
+            experience_years: 7,
+            location: "London".into(),
+            created_at: T0.into(),
+            updated_at: T0.into(),
+            resume_filename: None,
+            role: Role::User,
+            google_sub: None,
+        }
+    }
+
+    fn token(user_id: &str, family_id: &str) -> RefreshTokenRecord {
+        RefreshTokenRecord {
+            user_id: user_id.into(),
+            family_id: family_id.into(),
+            issued_at: T0.into(),
+            expires_at: T1.into(),
+            revoked_at: None,
+            replaced_by: None,
+        }
+    }
+
+    fn json<T: serde::Serialize>(value: &T) -> serde_json::Value {
+        serde_json::to_value(value).unwrap()
+    }
+
+    #[actix_web::test]
+    #[ignore = "needs Postgres: set TEST_DATABASE_URL and run with --ignored"]
+    async fn migrations_apply_once() {
+        let db = Scratch::create().await;
+        // Connecting again finds everything applied
+        let again = PgStore::connect(&Scratch::url(&db.admin_url, &db.name)).await.unwrap();
+        let versions: Vec<i64> = sqlx::query_scalar("SELECT version FROM schema_migrations ORDER BY version").fetch_all(&again.pool).await.unwrap();
+        assert_eq!(versions, MIGRATIONS.iter().map(|m| m.0).collect::<Vec<_>>());
+        again.pool.close().await;
+        db.drop_database().await;
+    }
+
+    #[actix_web::test]
+    #[ignore = "needs Postgres: set TEST_DATABASE_URL and run with --ignored"]
+    async fn users_round_trip_and_only_changed_rows_are_written() {
+        let db = Scratch::create().await;
+        let mut users = vec![user("u1", "ada@example.com"), user("u2", "grace@example.com")];
+        users[1].role = Role::Admin;
+        users[1].google_sub = Some("google-123".into());
+        users[1].password_hash = None;
+        db.storage.save_users(&users, &["u1", "u2"]).await.unwrap();
+        assert_eq!(json(&db.storage.load_users().await.unwrap()), json(&users));
+
+        // Only u1 is written; the stale copy of u2 in the snapshot doesn't overwrite it
+        let mut snapshot = users.clone();
+        snapshot[0].skills.push("postgres".into());
+        snapshot[0].updated_at = T1.into();
+        snapshot[1].first_name = "Stale".into();
+        db.storage.save_users(&snapshot, &["u1"]).await.unwrap();
+        let expected = vec![snapshot[0].clone(), users[1].clone()];
+        assert_eq!(json(&db.storage.load_users().await.unwrap()), json(&expected));
+
+        // A snapshot missing a user never deletes it
+        db.storage.save_users(&snapshot[..1], &["u1"]).await.unwrap();
+        assert_eq!(json(&db.storage.load_users().await.unwrap()), json(&expected));
+        db.drop_database().await;
+    }
+
+    #[actix_web::test]
+    #[ignore = "needs Postgres: set TEST_DATABASE_URL and run with --ignored"]
+    async fn refresh_tokens_are_upserted_and_deleted_by_hash() {
+        let db = Scratch::create().await;
+        db.storage.save_users(&[user("u1", "ada@example.com")], &["u1"]).await.unwrap();
+        let mut tokens = HashMap::new();
+        tokens.insert("h1".to_string(), token("u1", "f1"));
+        tokens.insert("h2".to_string(), token("u1", "f1"));
+        tokens.insert("h3".to_string(), token("u1", "f2"));
+        db.storage.save_refresh_tokens(&tokens, &["h1".into(), "h2".into(), "h3".into()]).await.unwrap();
+
+        // Rotation: h1 revoked and replaced by h2; h3 swept
+        let h1 = tokens.get_mut("h1").unwrap();
+        h1.revoked_at = Some(T1.into());
+        h1.replaced_by = Some("h2".into());
+        tokens.remove("h3");
+        db.storage.save_refresh_tokens(&tokens, &["h1".into(), "h3".into()]).await.unwrap();
+
+        let loaded = db.storage.load_refresh_tokens().await.unwrap();
+        assert_eq!(loaded.keys().collect::<HashSet<_>>(), tokens.keys().collect::<HashSet<_>>());
+        assert_eq!(json(&loaded), json(&tokens));
+        db.drop_database().await;
+    }
+
+    #[actix_web::test]
+    #[ignore = "needs Postgres: set TEST_DATABASE_URL and run with --ignored"]
+    async fn password_resets_are_upserted_and_deleted_by_hash() {
+        let db = Scratch::create().await;
+        db.storage.save_users(&[user("u1", "ada@example.com")], &["u1"]).await.unwrap();
+        let reset = |hash: &str| PasswordResetRecord {
+            token_hash: hash.into(),
+            user_id: "u1".into(),
+            created_at: T0.into(),
+            expires_at: T1.into(),
+            used_at: None,
+            attempts: 0,
+        };
+        let mut resets = vec![reset("r1"), reset("r2")];
+        db.storage.save_password_resets(&resets, &["r1".into(), "r2".into()]).await.unwrap();
+
+        // r1 spent after a wrong guess, r2 replaced by r3
+        resets[0].used_at = Some(T1.into());
+        resets[0].attempts = 1;
+        resets.remove(1);
+        resets.push(reset("r3"));
+        db.storage.save_password_resets(&resets, &["r1".into(), "r2".into(), "r3".into()]).await.unwrap();
+        assert_eq!(json(&db.storage.load_password_resets().await.unwrap()), json(&resets));
+        db.drop_database().await;
+    }
+
+    #[actix_web::test]
+    #[ignore = "needs Postgres: set TEST_DATABASE_URL and run with --ignored"]
+    async fn jobs_round_trip_in_catalog_order() {
+        let db = Scratch::create().await;
+        let mut first = new_job("remotive", "1", "Rust Engineer".into(), "Acme".into(), "https://acme.test/1".into(), "<p>Rust</p>".into());
+        first.created_at = T0.into();
+        first.location = Some("Europe".into());
+        first.location_info = Some(JobLocation { worldwide: false, regions: vec!["europe".into()], ..Default::default() });
+        first.salary = Some("$100k - $120k".into());
+        first.salary_range = Some(SalaryRange { min: Some(100000.0), max: Some(120000.0), currency: Some("USD".into()), period: SalaryPeriod::Year });
+        first.source_urls = vec![SourceUrl { job_id: first.id.clone(), source: "remotive".into(), url: first.url.clone() }];
+        let mut second = new_job("rss", "2", "Go Engineer".into(), "Initech".into(), "https://initech.test/2".into(), "Go".into());
+        second.created_at = T1.into();
+        second.expired_at = Some(T1.into());
+        second.duplicate_of = Some(first.id.clone());
+        let jobs = vec![first, second];
+        let all: HashSet<String> = jobs.iter().map(|j| j.id.clone()).collect();
+        db.storage.save_jobs(&jobs, &all).await.unwrap();
+        assert_eq!(db.storage.load_jobs().await.unwrap(), jobs);
+
+        // An empty snapshot changes nothing
+        db.storage.save_jobs(&[], &HashSet::new()).await.unwrap();
+        assert_eq!(db.storage.load_jobs().await.unwrap(), jobs);
+        db.drop_database().await;
+    }
+
+    #[actix_web::test]
+    #[ignore = "needs Postgres: set TEST_DATABASE_URL and run with --ignored"]
+    async fn resumes_are_recorded_once() {
+        let db = Scratch::create().await;
+        let resume = ResumeRecord {
+            id: "r1".into(),
+            user_id: "u1".into(),
+            application_id: Some("a1".into()),
+            version: Some(2),
+            filename: "app_a1_v2_cv.pdf".into(),
+            original_filename: "cv.pdf".into(),
+            content_type: "application/pdf".into(),
+            size_bytes: 2048,
+            uploaded_at: T0.into(),
+        };
+        db.storage.record_resume(resume.clone()).await.unwrap();
+        db.storage.record_resume(resume).await.unwrap();
+        type Row = (String, Option<String>, Option<i32>, String, i64, DateTime<Utc>);
+        let rows: Vec<Row> = sqlx::query_as(
+            "SELECT user_id, application_id, version, content_type, size_bytes, uploaded_at FROM resumes",
+        )
+        .fetch_all(&db.pg().pool)
+        .await
+        .unwrap();
+        assert_eq!(rows, vec![("u1".into(), Some("a1".into()), Some(2), "application/pdf".into(), 2048, timestamp(T0))]);
+        db.drop_database().await;
+    }
+}
@@ -0,0 +1,81 @@
+use std::future::{ready, Ready};
+
+use actix_web::{dev::Payload, error::InternalError, http::header, web, FromRequest, HttpRequest, HttpResponse};
+
+use crate::models::{ApiResponse, Role};
+use crate::state::AppState;
+use crate::utils::jwt::{verify_token, TokenType};
+use crate::utils::sessions::session_active;
+
+/// The caller, taken from an `Authorization: Bearer <access token>` header. Handlers
+/// that take it answer 401 when the token is missing, invalid, expired or a refresh token.
+pub struct AuthUser {
+    pub user_id: String,
+    pub role: Role,
+}
+
+impl AuthUser {
+    pub fn is_admin(&self) -> bool {
+        self.role == Role::Admin
+    }
+
+    /// Whether the caller may read or change resources belonging to `user_id`;
+    /// admins may act on anyone's.
+    pub fn can_access(&self, user_id: &str) -> bool {
+        self.is_admin() || self.user_id == user_id
+    }
+}
+
+/// Whether `email` is listed in `ADMIN_EMAILS`. Only accounts that already exist are
+/// promoted, at startup: sign-up doesn't prove the caller owns the email.
+pub fn listed_admin(email: &str) -> bool {
+    let admins = std::env::var("ADMIN_EMAILS").unwrap_or_default();
+    admins.split(',').any(|a| !a.trim().is_empty() && a.trim().eq_ignore_ascii_case(email.trim()))
+}
+
+pub fn forbidden() -> HttpResponse {
+    HttpResponse::Forbidden().json(ApiResponse::<serde_json::Value> {
+        success: false,
+        data: None,
+        message: "You don't have access to this resource".into(),
+    })
+}
+
+fn unauthorized(message: String) -> actix_web::Error {
+    let response = HttpResponse::Unauthorized()
+        .insert_header((header::WWW_AUTHENTICATE, "Bearer"))
+        .json(ApiResponse::<serde_json::Value> { success: false, data: None, message: message.clone() });
+    InternalError::from_response(message, response).into()
+}
+
+fn authenticate(req: &HttpRequest) -> Result<AuthUser, actix_web::Error> {
+    let state = req.app_data::<web::Data<AppState>>()
+        .ok_or_else(|| actix_web::error::ErrorInternalServerError("App state not configured"))?;
+    let token = req.headers().get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.split_once(' '))
+        .filter(|(scheme, _)| scheme.eq_ignore_ascii_case("bearer"))
+        .map(|(_, token)| token.trim())
+        .filter(|t| !t.is_empty())
+        .ok_or_else(|| unauthorized("Missing bearer token".into()))?;
+    let claims = verify_token(token, &state.jwt_secret, TokenType::Access).map_err(unauthorized)?;
+    // Tokens stay valid until they expire, even for accounts deleted or demoted since
+    let current_role = match state.users.lock().unwrap().iter().find(|u| u.id == claims.sub) {
+        Some(u) => u.role,
+        None => return Err(unauthorized("User no longer exists".into())),
+    };
+    if !session_active(state, &claims.sid) {
+        return Err(unauthorized("Session has ended".into()));
+    }
+    let role = if current_role == Role::Admin { claims.role } else { Role::User };
+    Ok(AuthUser { user_id: claims.sub, role })
+}
+
+impl FromRequest for AuthUser {
+    type Error = actix_web::Error;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        ready(authenticate(req))
+    }
+}
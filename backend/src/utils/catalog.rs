@@ -0,0 +1,71 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::models::Job;
+
+pub struct MergeOutcome {
+    pub added: Vec<Job>,
+    pub updated: usize,
+    pub expired: usize,
+}
+
+// Fields that come from the source; ids and timestamps we own are not compared
+fn same_content(a: &Job, b: &Job) -> bool {
+    a.title == b.title
+        && a.company == b.company
+        && a.company_logo == b.company_logo
+        && a.location == b.location
+        && a.location_info == b.location_info
+        && a.url == b.url
+        && a.description == b.description
+        && a.job_type == b.job_type
+        && a.salary == b.salary
+        && a.salary_range == b.salary_range
+        && a.category == b.category
+}
+
+/// Upsert one source's complete listing into the catalog by `external_id`.
+/// Existing jobs keep their internal id; jobs of that source missing from
+/// `incoming` are marked expired, and expired jobs that reappear are revived.
+pub fn merge_jobs(catalog: &mut Vec<Job>, source: &str, incoming: Vec<Job>, now: &str) -> MergeOutcome {
+    let index: HashMap<String, usize> = catalog.iter().enumerate()
+        .filter(|(_, j)| j.source_name() == source)
+        .filter_map(|(i, j)| j.external_id.clone().map(|e| (e, i)))
+        .collect();
+    let mut seen: HashSet<String> = HashSet::new();
+    let mut added: Vec<Job> = vec![];
+    let mut updated = 0;
+
+    for mut job in incoming.into_iter() {
+        let ext = match &job.external_id { Some(e) => e.clone(), None => continue };
+        if !seen.insert(ext.clone()) { continue; }
+        match index.get(&ext) {
+            Some(&i) => {
+                let existing = &mut catalog[i];
+                if !same_content(existing, &job) || existing.expired_at.is_some() {
+                    job.id = existing.id.clone();
+                    job.source = Some(source.to_string());
+                    job.expired_at = None;
+                    *existing = job;
+                    updated += 1;
+                }
+            }
+            None => {
+                job.source = Some(source.to_string());
+                job.expired_at = None;
+                added.push(job);
+            }
+        }
+    }
+
+    let mut expired = 0;
+    for job in catalog.iter_mut().filter(|j| j.source_name() == source) {
+        let missing = job.external_id.as_ref().map(|e| !seen.contains(e)).unwrap_or(false);
+        if missing && job.expired_at.is_none() {
+            job.expired_at = Some(now.to_string());
+            expired += 1;
+        }
+    }
+
+    catalog.extend(added.iter().cloned());
+    MergeOutcome { added, updated, expired }
+}
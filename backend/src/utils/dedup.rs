@@ -0,0 +1,119 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::models::{Job, SourceUrl};
+use crate::utils::text::{strip_html, tokenize};
+
+const COMPANY_SUFFIXES: [&str; 13] = ["inc", "llc", "ltd", "limited", "gmbh", "corp", "corporation", "co", "company", "sa", "ag", "bv", "plc"];
+const TITLE_NOISE: [&str; 4] = ["remote", "fulltime", "parttime", "hiring"];
+const SHINGLE_SIZE: usize = 3;
+// Below this many shingles a description says too little to compare
+const MIN_SHINGLES: usize = 5;
+const TITLE_SIMILARITY: f64 = 0.8;
+const DESCRIPTION_SIMILARITY: f64 = 0.5;
+
+/// What two postings are compared on: company, title words and description shingles.
+pub struct Fingerprint {
+    pub company: String,
+    pub title: HashSet<String>,
+    pub shingles: HashSet<String>,
+}
+
+pub fn fingerprint(job: &Job) -> Fingerprint {
+    let company = tokenize(&job.company).into_iter()
+        .filter(|t| !COMPANY_SUFFIXES.contains(&t.as_str()))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let title = tokenize(&job.title).into_iter()
+        .map(|t| match t.as_str() {
+            "sr" => "senior".to_string(),
+            "jr" => "junior".to_string(),
+            "dev" => "developer".to_string(),
+            "eng" => "engineer".to_string(),
+            _ => t,
+        })
+        .filter(|t| !TITLE_NOISE.contains(&t.as_str()))
+        .collect();
+    let words = tokenize(&strip_html(&job.description));
+    let shingles = if words.len() < SHINGLE_SIZE {
+        words.into_iter().collect()
+    } else {
+        words.windows(SHINGLE_SIZE).map(|w| w.join(" ")).collect()
+    };
+    Fingerprint { company, title, shingles }
+}
+
+fn jaccard(a: &HashSet<String>, b: &HashSet<String>) -> f64 {
+    if a.is_empty() && b.is_empty() { return 1.0; }
+    let shared = a.intersection(b).count();
+    shared as f64 / (a.len() + b.len() - shared) as f64
+}
+
+/// Same company, (nearly) the same title, and overlapping descriptions. When either
+/// description is too short to judge, the titles have to match exactly instead.
+pub fn is_duplicate(a: &Fingerprint, b: &Fingerprint) -> bool {
+    if a.company.is_empty() || a.company != b.company { return false; }
+    if a.shingles.len() >= MIN_SHINGLES && b.shingles.len() >= MIN_SHINGLES {
+        jaccard(&a.title, &b.title) >= TITLE_SIMILARITY && jaccard(&a.shingles, &b.shingles) >= DESCRIPTION_SIMILARITY
+    } else {
+        a.title == b.title
+    }
+}
+
+fn find(parent: &mut [usize], i: usize) -> usize {
+    let mut root = i;
+    while parent[root] != root { root = parent[root]; }
+    parent[i] = root;
+    root
+}
+
+/// Group active jobs that are the same posting and mark all but one as duplicates.
+/// The canonical job is the one that entered the catalog first (lowest index), so
+/// it stays stable across syncs; it collects the URL of every copy in `source_urls`.
+/// Returns the number of jobs marked as duplicates.
+pub fn dedupe_jobs(catalog: &mut [Job]) -> usize {
+    for job in catalog.iter_mut() {
+        job.duplicate_of = None;
+        job.source_urls.clear();
+    }
+
+    let active: Vec<usize> = (0..catalog.len()).filter(|&i| catalog[i].expired_at.is_none()).collect();
+    let prints: HashMap<usize, Fingerprint> = active.iter().map(|&i| (i, fingerprint(&catalog[i]))).collect();
+    let mut by_company: HashMap<&str, Vec<usize>> = HashMap::new();
+    for &i in active.iter() {
+        by_company.entry(prints[&i].company.as_str()).or_default().push(i);
+    }
+
+    let mut parent: Vec<usize> = (0..catalog.len()).collect();
+    for members in by_company.values().filter(|m| m.len() > 1) {
+        for (n, &i) in members.iter().enumerate() {
+            for &j in members[n + 1..].iter() {
+                if is_duplicate(&prints[&i], &prints[&j]) {
+                    let (ri, rj) = (find(&mut parent, i), find(&mut parent, j));
+                    // Keep the earliest catalog entry as the root
+                    if ri != rj { parent[ri.max(rj)] = ri.min(rj); }
+                }
+            }
+        }
+    }
+
+    let mut groups: HashMap<usize, Vec<usize>> = HashMap::new();
+    for &i in active.iter() {
+        let root = find(&mut parent, i);
+        groups.entry(root).or_default().push(i);
+    }
+
+    let mut duplicates = 0;
+    for (root, members) in groups.into_iter().filter(|(_, m)| m.len() > 1) {
+        let canonical_id = catalog[root].id.clone();
+        let mut urls: Vec<SourceUrl> = vec![];
+        for &i in members.iter() {
+            urls.push(SourceUrl { job_id: catalog[i].id.clone(), source: catalog[i].source_name().to_string(), url: catalog[i].url.clone() });
+            if i != root {
+                catalog[i].duplicate_of = Some(canonical_id.clone());
+                duplicates += 1;
+            }
+        }
+        catalog[root].source_urls = urls;
+    }
+    duplicates
+}
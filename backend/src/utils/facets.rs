@@ -0,0 +1,52 @@
+use std::collections::HashMap;
+
+use crate::models::{FacetCount, Job, SalaryBucket};
+use crate::utils::location::{eligibility, job_location, resolve_place, Eligibility, REGIONS};
+
+// Yearly bands; a job counts in every band its range overlaps, like the salary filters
+const SALARY_BUCKETS: [(&str, Option<f64>, Option<f64>); 5] = [
+    ("under_50k", None, Some(50_000.0)),
+    ("50k_100k", Some(50_000.0), Some(100_000.0)),
+    ("100k_150k", Some(100_000.0), Some(150_000.0)),
+    ("150k_200k", Some(150_000.0), Some(200_000.0)),
+    ("200k_plus", Some(200_000.0), None),
+];
+
+/// Count distinct values, most frequent first, keeping at most `limit`.
+pub fn value_counts<S: AsRef<str>>(values: impl Iterator<Item = S>, limit: usize) -> Vec<FacetCount> {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for v in values {
+        let v = v.as_ref().trim();
+        if !v.is_empty() { *counts.entry(v.to_string()).or_insert(0) += 1; }
+    }
+    let mut out: Vec<FacetCount> = counts.into_iter().map(|(value, count)| FacetCount { value, count }).collect();
+    out.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.value.cmp(&b.value)));
+    out.truncate(limit);
+    out
+}
+
+/// Jobs open to someone in each region, as the `region` filter decides it.
+pub fn region_counts(jobs: &[&Job]) -> Vec<FacetCount> {
+    let locations: Vec<_> = jobs.iter().map(|j| job_location(j)).collect();
+    let mut out: Vec<FacetCount> = REGIONS.iter()
+        .filter_map(|r| resolve_place(r).map(|place| (r, place)))
+        .map(|(r, place)| FacetCount {
+            value: r.to_string(),
+            count: locations.iter().filter(|l| eligibility(l, &place) == Eligibility::Eligible).count(),
+        })
+        .filter(|f| f.count > 0)
+        .collect();
+    out.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.value.cmp(&b.value)));
+    out
+}
+
+pub fn salary_counts(jobs: &[&Job]) -> Vec<SalaryBucket> {
+    SALARY_BUCKETS.iter()
+        .map(|(value, min, max)| SalaryBucket {
+            value: value.to_string(),
+            min: *min,
+            max: *max,
+            count: jobs.iter().filter(|j| j.salary_range.as_ref().map(|r| r.overlaps(*min, *max)).unwrap_or(false)).count(),
+        })
+        .collect()
+}
@@ -0,0 +1,256 @@
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use jsonwebtoken::jwk::JwkSet;
+use jsonwebtoken::{decode, decode_header, Algorithm, DecodingKey, Validation};
+use serde::Deserialize;
+
+const DEFAULT_JWKS_URL: &str = "https://www.googleapis.com/oauth2/v3/certs";
+const ISSUERS: [&str; 2] = ["accounts.google.com", "https://accounts.google.com"];
+// Used when the JWKS response has no Cache-Control max-age
+const DEFAULT_CACHE_SECS: u64 = 3600;
+// An unknown key id triggers a refetch (Google rotated its keys), at most this often
+const MIN_REFETCH_SECS: u64 = 60;
+
+pub enum GoogleError {
+    NotConfigured,
+    // The signing keys couldn't be fetched or read
+    KeysUnavailable(String),
+    Invalid(String),
+}
+
+/// Claims of a verified Google ID token that sign-in uses.
+#[derive(Debug, Deserialize)]
+pub struct GoogleIdentity {
+    pub sub: String,
+    pub email: String,
+    #[serde(default)]
+    pub given_name: Option<String>,
+    #[serde(default)]
+    pub family_name: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct IdTokenClaims {
+    #[serde(flatten)]
+    identity: GoogleIdentity,
+    // Google sends a bool; some older tokens carry the string "true"
+    #[serde(default)]
+    email_verified: serde_json::Value,
+}
+
+struct CachedKeys {
+    keys: JwkSet,
+    fetched_at: Instant,
+    max_age: Duration,
+}
+
+/// Checks Google ID tokens: RS256 signature against Google's published keys (or a
+/// local JWKS file), issuer, audience (`GOOGLE_CLIENT_IDS`), expiry and a verified email.
+pub struct GoogleVerifier {
+    client_ids: Vec<String>,
+    jwks_url: String,
+    jwks_file: Option<PathBuf>,
+    cache: Mutex<Option<CachedKeys>>,
+}
+
+impl GoogleVerifier {
+    pub fn from_env() -> Self {
+        let client_ids = std::env::var("GOOGLE_CLIENT_IDS").unwrap_or_default()
+            .split(',').map(|s| s.trim().to_string()).filter(|s| !s.is_empty()).collect();
+        GoogleVerifier {
+            client_ids,
+            jwks_url: std::env::var("GOOGLE_JWKS_URL").unwrap_or_else(|_| DEFAULT_JWKS_URL.to_string()),
+            jwks_file: std::env::var("GOOGLE_JWKS_FILE").ok().filter(|s| !s.trim().is_empty()).map(PathBuf::from),
+            cache: Mutex::new(None),
+        }
+    }
+
+    async fn fetch_keys(&self) -> Result<CachedKeys, String> {
+        if let Some(path) = &self.jwks_file {
+            let content = std::fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+            let keys = serde_json::from_str(&content).map_err(|e| format!("Invalid JWKS in {}: {}", path.display(), e))?;
+            return Ok(CachedKeys { keys, fetched_at: Instant::now(), max_age: Duration::from_secs(DEFAULT_CACHE_SECS) });
+        }
+        let response = reqwest::Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .map_err(|e| format!("Failed to create HTTP client: {}", e))?
+            .get(&self.jwks_url)
+            .send()
+            .await
+            .map_err(|e| format!("Failed to fetch Google keys: {}", e))?;
+        if !response.status().is_success() {
+            return Err(format!("Failed to fetch Google keys: HTTP {}", response.status()));
+        }
+        let max_age = response.headers().get(reqwest::header::CACHE_CONTROL)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.split(',').find_map(|d| d.trim().strip_prefix("max-age=").and_then(|s| s.parse().ok())))
+            .unwrap_or(DEFAULT_CACHE_SECS);
+        let keys = response.json::<JwkSet>().await.map_err(|e| format!("Invalid Google keys: {}", e))?;
+        Ok(CachedKeys { keys, fetched_at: Instant::now(), max_age: Duration::from_secs(max_age) })
+    }
+
+    /// The decoding key for `kid`, from the cache while it's fresh.
+    async fn key_for(&self, kid: &str) -> Result<DecodingKey, GoogleError> {
+        let cached = {
+            let cache = self.cache.lock().unwrap();
+            cache.as_ref().map(|c| {
+                let fresh = c.fetched_at.elapsed() < c.max_age;
+                let may_refetch = c.fetched_at.elapsed() >= Duration::from_secs(MIN_REFETCH_SECS);
+                (c.keys.find(kid).cloned(), fresh, may_refetch)
+            })
+        };
+        let jwk = match cached {
+            Some((Some(jwk), true, _)) => jwk,
+            Some((None, true, false)) => return Err(GoogleError::Invalid("Unknown signing key".into())),
+            _ => {
+                let fetched = self.fetch_keys().await.map_err(GoogleError::KeysUnavailable)?;
+                let jwk = fetched.keys.find(kid).cloned();
+                *self.cache.lock().unwrap() = Some(fetched);
+                jwk.ok_or_else(|| GoogleError::Invalid("Unknown signing key".into()))?
+            }
+        };
+        DecodingKey::from_jwk(&jwk).map_err(|e| GoogleError::KeysUnavailable(format!("Unusable signing key: {}", e)))
+    }
+
+    pub async fn verify(&self, id_token: &str) -> Result<GoogleIdentity, GoogleError> {
+        if self.client_ids.is_empty() {
+            return Err(GoogleError::NotConfigured);
+        }
+        let invalid = |msg: String| GoogleError::Invalid(format!("Invalid Google id_token: {}", msg));
+        let header = decode_header(id_token).map_err(|e| invalid(e.to_string()))?;
+        if header.alg != Algorithm::RS256 {
+            return Err(invalid("unexpected algorithm".into()));
+        }
+        let kid = header.kid.ok_or_else(|| invalid("no key id".into()))?;
+        let key = self.key_for(&kid).await?;
+
+        let mut validation = Validation::new(Algorithm::RS256);
+        validation.set_audience(&self.client_ids);
+        validation.set_issuer(&ISSUERS);
+        validation.set_required_spec_claims(&["exp", "iss", "aud", "sub"]);
+        let claims = decode::<IdTokenClaims>(id_token, &key, &validation)
+            .map_err(|e| invalid(e.to_string()))?
+            .claims;
+
+        let verified = claims.email_verified.as_bool().unwrap_or(false) || claims.email_verified.as_str() == Some("true");
+        if !verified {
+            return Err(GoogleError::Invalid("Google account email is not verified".into()));
+        }
+        Ok(claims.identity)
+    }
+}
+
+/// A verifier trusting only the fixture key (`fixtures/google`), and ID tokens signed with it.
+#[cfg(test)]
+pub mod testing {
+    use super::*;
+    use jsonwebtoken::{encode, EncodingKey, Header};
+
+    pub const CLIENT_ID: &str = "test-client.apps.googleusercontent.com";
+    const JWKS: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/google/jwks.json");
+    const KEY: &[u8] = include_bytes!("../../fixtures/google/test_key.pem");
+
+    pub fn verifier() -> GoogleVerifier {
+        GoogleVerifier {
+            client_ids: vec![CLIENT_ID.to_string()],
+            jwks_url: DEFAULT_JWKS_URL.to_string(),
+            jwks_file: Some(PathBuf::from(JWKS)),
+            cache: Mutex::new(None),
+        }
+    }
+
+    /// Claims Google would send for a verified account, valid for the next hour.
+    pub fn claims(sub: &str, email: &str) -> serde_json::Value {
+        let now = chrono::Utc::now().timestamp();
+        serde_json::json!({
+            "iss": "https://accounts.google.com",
+            "aud": CLIENT_ID,
+            "sub": sub,
+            "email": email,
+            "email_verified": true,
+            "given_name": "Ada",
+            "family_name": "Lovelace",
+            "iat": now,
+            "exp": now + 3600,
+        })
+    }
+
+    pub fn sign_with_kid(claims: &serde_json::Value, kid: &str) -> String {
+        let mut header = Header::new(Algorithm::RS256);
+        header.kid = Some(kid.to_string());
+        encode(&header, claims, &EncodingKey::from_rsa_pem(KEY).unwrap()).unwrap()
+    }
+
+    pub fn sign(claims: &serde_json::Value) -> String {
+        sign_with_kid(claims, "test-key")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::testing::{claims, sign, sign_with_kid, verifier};
+    use super::*;
+    use jsonwebtoken::{encode, EncodingKey, Header};
+
+    async fn rejection(token: &str) -> String {
+        match verifier().verify(token).await {
+            Ok(identity) => panic!("accepted a bad token for {}", identity.sub),
+            Err(GoogleError::Invalid(msg)) => msg,
+            Err(GoogleError::KeysUnavailable(msg)) => panic!("keys unavailable: {}", msg),
+            Err(GoogleError::NotConfigured) => panic!("not configured"),
+        }
+    }
+
+    #[actix_web::test]
+    async fn accepts_a_valid_token() {
+        let identity = verifier().verify(&sign(&claims("1234", "ada@example.com"))).await.ok().unwrap();
+        assert_eq!(identity.sub, "1234");
+        assert_eq!(identity.email, "ada@example.com");
+        assert_eq!(identity.given_name.as_deref(), Some("Ada"));
+        assert_eq!(identity.family_name.as_deref(), Some("Lovelace"));
+
+        // The bare issuer and a string "true" are accepted as well
+        let mut c = claims("1234", "ada@example.com");
+        c["iss"] = "accounts.google.com".into();
+        c["email_verified"] = "true".into();
+        assert!(verifier().verify(&sign(&c)).await.is_ok());
+    }
+
+    #[actix_web::test]
+    async fn rejects_expired_and_misaddressed_tokens() {
+        let mut expired = claims("1234", "ada@example.com");
+        expired["exp"] = (chrono::Utc::now().timestamp() - 3600).into();
+        assert!(rejection(&sign(&expired)).await.contains("ExpiredSignature"));
+
+        let mut wrong_aud = claims("1234", "ada@example.com");
+        wrong_aud["aud"] = "someone-else.apps.googleusercontent.com".into();
+        assert!(rejection(&sign(&wrong_aud)).await.contains("InvalidAudience"));
+
+        let mut wrong_iss = claims("1234", "ada@example.com");
+        wrong_iss["iss"] = "https://evil.example.com".into();
+        assert!(rejection(&sign(&wrong_iss)).await.contains("InvalidIssuer"));
+    }
+
+    #[actix_web::test]
+    async fn rejects_other_algorithms_and_unknown_keys() {
+        let mut header = Header::new(Algorithm::HS256);
+        header.kid = Some("test-key".into());
+        let hs256 = encode(&header, &claims("1234", "ada@example.com"), &EncodingKey::from_secret(b"secret")).unwrap();
+        assert!(rejection(&hs256).await.contains("unexpected algorithm"));
+
+        let unknown_kid = sign_with_kid(&claims("1234", "ada@example.com"), "rotated-away");
+        assert_eq!(rejection(&unknown_kid).await, "Unknown signing key");
+    }
+
+    #[actix_web::test]
+    async fn rejects_unverified_emails() {
+        for verified in [serde_json::json!(false), serde_json::json!("false"), serde_json::Value::Null] {
+            let mut c = claims("1234", "ada@example.com");
+            c["email_verified"] = verified;
+            assert_eq!(rejection(&sign(&c)).await, "Google account email is not verified");
+        }
+    }
+}
@@ -0,0 +1,23 @@
+use uuid::Uuid;
+
+// UUIDv5 of "https://joblens.app/jobs" in the URL namespace
+const JOB_NAMESPACE: Uuid = Uuid::from_u128(0xde1355c3_cf8d_53e9_893f_f8ff50e0075b);
+
+/// Deterministic job id for a posting, so the same upstream job always gets the
+/// same id no matter which request or sync built it.
+pub fn stable_job_id(source: &str, external_id: &str) -> String {
+    Uuid::new_v5(&JOB_NAMESPACE, format!("{}:{}", source, external_id).as_bytes()).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ids_depend_on_source_and_external_id() {
+        // Stored ids must not change between releases
+        assert_eq!(stable_job_id("remotive", "1912399"), "3d5d4109-6aa6-51f8-9bf3-ea67f5163e3d");
+        // The same external id from another source is another posting
+        assert_ne!(stable_job_id("remotive", "1912399"), stable_job_id("example-rss", "1912399"));
+    }
+}
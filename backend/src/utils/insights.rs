@@ -0,0 +1,171 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+use crate::models::{Application, ApplicationInsights, ApplicationStatus, CategoryOutcomes, Job, User};
+use crate::utils::matching::compute_weighted_match;
+use crate::utils::text::normalize_token;
+
+// Used when nobody has a decided application yet
+const DEFAULT_BASE_RATE: f64 = 0.2;
+// Pseudo-count pulling small categories towards the platform-wide rate
+const PRIOR_WEIGHT: f64 = 5.0;
+
+/// Outcome of an application for learning purposes: `Some(true)` once it reached
+/// interviewing or was accepted, `Some(false)` when rejected, `None` while undecided.
+pub fn application_outcome(app: &Application) -> Option<bool> {
+    let reached_interview = app.status_history.iter().any(|h| h.status == ApplicationStatus::Interviewing);
+    match app.status {
+        ApplicationStatus::Accepted | ApplicationStatus::Interviewing => Some(true),
+        ApplicationStatus::Rejected => Some(reached_interview),
+        _ => None,
+    }
+}
+
+/// Smoothed success rate for a category, computed from other applications only.
+pub fn category_outcomes(category: Option<&str>, exclude_id: &str, all: &[Application]) -> CategoryOutcomes {
+    let decided: Vec<(&Application, bool)> = all.iter()
+        .filter(|a| a.id != exclude_id)
+        .filter_map(|a| application_outcome(a).map(|o| (a, o)))
+        .collect();
+
+    let base_rate = if decided.is_empty() { DEFAULT_BASE_RATE } else {
+        decided.iter().filter(|(_, o)| *o).count() as f64 / decided.len() as f64
+    };
+
+    let in_category: Vec<bool> = decided.iter()
+        .filter(|(a, _)| match (category, a.job_category.as_deref()) {
+            (Some(c), Some(ac)) => c.eq_ignore_ascii_case(ac),
+            _ => false,
+        })
+        .map(|(_, o)| *o)
+        .collect();
+    let successes = in_category.iter().filter(|o| **o).count();
+    let sample_size = in_category.len();
+    let success_rate = (successes as f64 + PRIOR_WEIGHT * base_rate) / (sample_size as f64 + PRIOR_WEIGHT);
+
+    CategoryOutcomes { category: category.map(|c| c.to_string()), sample_size, successes, success_rate, base_rate }
+}
+
+static YEARS_REQUIRED: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?i)(\d{1,2})\s*\+?\s*(?:-\s*\d{1,2}\s*)?(?:years?|yrs?)").unwrap());
+
+/// Smallest "N years" / "N+ years" requirement mentioned in a job description.
+pub fn required_experience_years(description: &str) -> Option<i32> {
+    YEARS_REQUIRED.captures_iter(description)
+        .filter_map(|c| c.get(1)?.as_str().parse::<i32>().ok())
+        .filter(|y| *y > 0 && *y <= 20)
+        .min()
+}
+
+/// 1.0 when the user meets the requirement, decaying by 0.2 per missing year.
+fn experience_fit(user_years: i32, required: Option<i32>) -> f64 {
+    match required {
+        Some(req) if user_years < req => (1.0 - 0.2 * (req - user_years) as f64).max(0.0),
+        Some(_) => 1.0,
+        None => 0.7,
+    }
+}
+
+fn skill_set(skills: &[String]) -> HashSet<String> {
+    skills.iter().map(|s| normalize_token(s)).filter(|s| !s.is_empty()).collect()
+}
+
+fn jaccard(a: &HashSet<String>, b: &HashSet<String>) -> f64 {
+    let union = a.union(b).count();
+    if union == 0 { return 0.0; }
+    a.intersection(b).count() as f64 / union as f64
+}
+
+/// Anonymised summaries of other users who succeeded in the same category,
+/// most similar skill sets first.
+fn similar_successful_profiles(user: &User, category: Option<&str>, all: &[Application], users: &[User]) -> Vec<String> {
+    let category = match category { Some(c) => c, None => return vec![] };
+    let mine = skill_set(&user.skills);
+    let mut seen: HashSet<&str> = HashSet::new();
+    let mut profiles: Vec<(f64, String)> = vec![];
+
+    for app in all.iter() {
+        if app.user_id == user.id || application_outcome(app) != Some(true) { continue; }
+        if !app.job_category.as_deref().map(|c| c.eq_ignore_ascii_case(category)).unwrap_or(false) { continue; }
+        if !seen.insert(app.user_id.as_str()) { continue; }
+        let peer = match users.iter().find(|u| u.id == app.user_id) { Some(u) => u, None => continue };
+        let theirs = skill_set(&peer.skills);
+        let similarity = jaccard(&mine, &theirs);
+        let mut shared: Vec<String> = mine.intersection(&theirs).cloned().collect();
+        shared.sort();
+        let mut extra: Vec<String> = theirs.difference(&mine).cloned().collect();
+        extra.sort();
+        extra.truncate(3);
+        let mut summary = format!("{} yrs experience, {} reached {}", peer.experience_years, app.job_title.as_deref().unwrap_or("a role"), app.status.as_str());
+        if !shared.is_empty() { summary.push_str(&format!("; shares {}", shared.join(", "))); }
+        if !extra.is_empty() { summary.push_str(&format!("; also knows {}", extra.join(", "))); }
+        profiles.push((similarity, summary));
+    }
+
+    profiles.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+    profiles.into_iter().take(3).map(|(_, s)| s).collect()
+}
+
+pub fn compute_insights(app: &Application, user: &User, job: Option<&Job>, all: &[Application], users: &[User], idf: &HashMap<String, f64>) -> ApplicationInsights {
+    let category = app.job_category.clone().or_else(|| job.and_then(|j| j.category.clone()));
+
+    // Prefer a fresh match against the cached job; fall back to the score recorded at apply time
+    let (match_score, matching_skills, mut missing_skills) = match job {
+        Some(j) => compute_weighted_match(&user.skills, &format!("{}\n{}", j.title, j.description), idf),
+        None => (app.match_score.unwrap_or(50.0), vec![], vec![]),
+    };
+    // Rarer skills carry more weight, so suggest those first
+    missing_skills.sort_by(|a, b| {
+        let wa = idf.get(a).unwrap_or(&1.0);
+        let wb = idf.get(b).unwrap_or(&1.0);
+        wb.partial_cmp(wa).unwrap_or(std::cmp::Ordering::Equal)
+    });
+
+    let required_years = job.and_then(|j| required_experience_years(&j.description));
+    let exp_fit = experience_fit(user.experience_years, required_years);
+    let outcomes = category_outcomes(category.as_deref(), &app.id, all);
+
+    let probability = 0.5 * (match_score / 100.0) + 0.3 * outcomes.success_rate + 0.2 * exp_fit;
+    let success_probability = (probability.clamp(0.02, 0.98) * 100.0).round() / 100.0;
+
+    let mut suggestions: Vec<String> = vec![];
+    for skill in missing_skills.iter().take(3) {
+        suggestions.push(format!("Highlight or build experience with {}; this job does not mention it among your skills", skill));
+    }
+    if let Some(req) = required_years {
+        if user.experience_years < req {
+            suggestions.push(format!("The role asks for {}+ years; emphasise projects that cover the {} year gap", req, req - user.experience_years));
+        }
+    }
+    if job.is_some() && matching_skills.is_empty() && !user.skills.is_empty() {
+        suggestions.push("None of your listed skills appear in the job description; tailor your profile to the posting".to_string());
+    }
+    if user.skills.is_empty() {
+        suggestions.push("Add skills to your profile so applications can be matched accurately".to_string());
+    }
+    if app.resume_filename.is_none() {
+        suggestions.push("Attach a resume to this application".to_string());
+    }
+    if app.cover_letter.as_deref().map(|c| c.trim().is_empty()).unwrap_or(true) {
+        suggestions.push("Include a cover letter tailored to the company".to_string());
+    }
+    if outcomes.sample_size >= 3 && outcomes.success_rate < outcomes.base_rate {
+        suggestions.push(format!(
+            "Applications in {} succeed less often than average ({:.0}% vs {:.0}%); consider widening your search",
+            category.as_deref().unwrap_or("this category"), outcomes.success_rate * 100.0, outcomes.base_rate * 100.0
+        ));
+    }
+
+    ApplicationInsights {
+        application_id: app.id.clone(),
+        success_probability,
+        improvement_suggestions: suggestions,
+        similar_successful_profiles: similar_successful_profiles(user, category.as_deref(), all, users),
+        match_score,
+        matching_skills,
+        missing_skills,
+        required_experience_years: required_years,
+        category_outcomes: outcomes,
+    }
+}
@@ -1,19 +1,55 @@
 use chrono::{Duration, Utc};
+use uuid::Uuid;
 use jsonwebtoken::{decode, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::models::Role;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TokenType {
+    Access,
+    Refresh,
+}
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Claims {
     pub sub: String,
     pub exp: usize,
+    // Keeps a refresh token from being used as an access token and vice versa
+    pub token_type: TokenType,
+    // Tokens issued before roles existed carry none and count as a plain user
+    #[serde(default)]
+    pub role: Role,
+    // Session (refresh token family) the token belongs to; ending the session voids it
+    #[serde(default)]
+    pub sid: String,
+    // Unique per token, so two issued in the same second never collide
+    #[serde(default)]
+    pub jti: String,
 }
 
-pub fn make_tokens(user_id: &str, jwt_secret: &str) -> (String, String, String, String) {
+pub fn make_tokens(user_id: &str, role: Role, session_id: &str, jwt_secret: &str) -> (String, String, String, String) {
     let access_exp = Utc::now() + Duration::minutes(60);
     let refresh_exp = Utc::now() + Duration::days(30);
 
-    let access_claims = Claims { sub: user_id.to_string(), exp: access_exp.timestamp() as usize };
-    let refresh_claims = Claims { sub: user_id.to_string(), exp: refresh_exp.timestamp() as usize };
+    let access_claims = Claims {
+        sub: user_id.to_string(),
+        exp: access_exp.timestamp() as usize,
+        token_type: TokenType::Access,
+        role,
+        sid: session_id.to_string(),
+        jti: Uuid::new_v4().to_string(),
+    };
+    let refresh_claims = Claims {
+        sub: user_id.to_string(),
+        exp: refresh_exp.timestamp() as usize,
+        token_type: TokenType::Refresh,
+        role,
+        sid: session_id.to_string(),
+        jti: Uuid::new_v4().to_string(),
+    };
 
     let access_token = encode(&Header::new(Algorithm::HS256), &access_claims, &EncodingKey::from_secret(jwt_secret.as_ref())).unwrap();
     let refresh_token = encode(&Header::new(Algorithm::HS256), &refresh_claims, &EncodingKey::from_secret(jwt_secret.as_ref())).unwrap();
@@ -21,11 +57,16 @@ pub fn make_tokens(user_id: &str, jwt_secret: &str) -> (String, String, String,
     (access_token, refresh_token, access_exp.to_rfc3339(), refresh_exp.to_rfc3339())
 }
 
-pub fn verify_token(token: &str, jwt_secret: &str) -> Result<String, String> {
+pub fn verify_token(token: &str, jwt_secret: &str, expected: TokenType) -> Result<Claims, String> {
     let validation = Validation::new(Algorithm::HS256);
     match decode::<Claims>(token, &DecodingKey::from_secret(jwt_secret.as_ref()), &validation) {
-        Ok(data) => Ok(data.claims.sub),
+        Ok(data) if data.claims.token_type == expected => Ok(data.claims),
+        Ok(_) => Err("Invalid token: wrong token type".to_string()),
         Err(e) => Err(format!("Invalid token: {}", e)),
     }
 }
 
+/// Refresh tokens are kept and looked up by this hash, never stored as issued.
+pub fn hash_token(token: &str) -> String {
+    Sha256::digest(token.as_bytes()).iter().map(|b| format!("{:02x}", b)).collect()
+}
@@ -0,0 +1,263 @@
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+use crate::models::{Job, JobLocation, TimezoneBand};
+
+struct Country {
+    code: &'static str,
+    // Lowercase names, common abbreviations and major cities
+    names: &'static [&'static str],
+    // Including parent regions, e.g. Germany is in both `europe` and `emea`
+    regions: &'static [&'static str],
+    utc: (f64, f64),
+}
+
+const EUROPE: &[&str] = &["europe", "emea"];
+const MIDDLE_EAST: &[&str] = &["middle_east", "emea"];
+const AFRICA: &[&str] = &["africa", "emea"];
+const NORTH_AMERICA: &[&str] = &["north_america", "americas"];
+const LATAM: &[&str] = &["latam", "americas"];
+const ASIA: &[&str] = &["apac"];
+const OCEANIA: &[&str] = &["oceania", "apac"];
+
+const COUNTRIES: &[Country] = &[
+    Country { code: "US", names: &["united states", "usa", "us", "u s", "u s a", "new york", "nyc", "san francisco", "california", "texas", "seattle", "boston", "chicago", "austin"], regions: NORTH_AMERICA, utc: (-8.0, -5.0) },
+    Country { code: "CA", names: &["canada", "toronto", "vancouver", "montreal"], regions: NORTH_AMERICA, utc: (-8.0, -3.5) },
+    Country { code: "MX", names: &["mexico", "mexico city"], regions: &["north_america", "latam", "americas"], utc: (-8.0, -5.0) },
+    Country { code: "BR", names: &["brazil", "brasil", "sao paulo"], regions: LATAM, utc: (-5.0, -3.0) },
+    Country { code: "AR", names: &["argentina", "buenos aires"], regions: LATAM, utc: (-3.0, -3.0) },
+    Country { code: "CO", names: &["colombia", "bogota"], regions: LATAM, utc: (-5.0, -5.0) },
+    Country { code: "CL", names: &["chile", "santiago"], regions: LATAM, utc: (-4.0, -3.0) },
+    Country { code: "PE", names: &["peru", "lima"], regions: LATAM, utc: (-5.0, -5.0) },
+    Country { code: "GB", names: &["united kingdom", "uk", "u k", "great britain", "britain", "england", "scotland", "wales", "london", "manchester"], regions: EUROPE, utc: (0.0, 0.0) },
+    Country { code: "IE", names: &["ireland", "dublin"], regions: EUROPE, utc: (0.0, 0.0) },
+    Country { code: "PT", names: &["portugal", "lisbon", "porto"], regions: EUROPE, utc: (0.0, 0.0) },
+    Country { code: "ES", names: &["spain", "madrid", "barcelona"], regions: EUROPE, utc: (1.0, 1.0) },
+    Country { code: "FR", names: &["france", "paris"], regions: EUROPE, utc: (1.0, 1.0) },
+    Country { code: "DE", names: &["germany", "deutschland", "berlin", "munich", "hamburg"], regions: EUROPE, utc: (1.0, 1.0) },
+    Country { code: "NL", names: &["netherlands", "the netherlands", "holland", "amsterdam"], regions: EUROPE, utc: (1.0, 1.0) },
+    Country { code: "BE", names: &["belgium", "brussels"], regions: EUROPE, utc: (1.0, 1.0) },
+    Country { code: "CH", names: &["switzerland", "zurich"], regions: EUROPE, utc: (1.0, 1.0) },
+    Country { code: "AT", names: &["austria", "vienna"], regions: EUROPE, utc: (1.0, 1.0) },
+    Country { code: "IT", names: &["italy", "milan", "rome"], regions: EUROPE, utc: (1.0, 1.0) },
+    Country { code: "PL", names: &["poland", "warsaw", "krakow"], regions: EUROPE, utc: (1.0, 1.0) },
+    Country { code: "CZ", names: &["czech republic", "czechia", "prague"], regions: EUROPE, utc: (1.0, 1.0) },
+    Country { code: "HU", names: &["hungary", "budapest"], regions: EUROPE, utc: (1.0, 1.0) },
+    Country { code: "HR", names: &["croatia", "zagreb"], regions: EUROPE, utc: (1.0, 1.0) },
+    Country { code: "RS", names: &["serbia", "belgrade"], regions: EUROPE, utc: (1.0, 1.0) },
+    Country { code: "SE", names: &["sweden", "stockholm"], regions: EUROPE, utc: (1.0, 1.0) },
+    Country { code: "NO", names: &["norway", "oslo"], regions: EUROPE, utc: (1.0, 1.0) },
+    Country { code: "DK", names: &["denmark", "copenhagen"], regions: EUROPE, utc: (1.0, 1.0) },
+    Country { code: "FI", names: &["finland", "helsinki"], regions: EUROPE, utc: (2.0, 2.0) },
+    Country { code: "EE", names: &["estonia", "tallinn"], regions: EUROPE, utc: (2.0, 2.0) },
+    Country { code: "LV", names: &["latvia", "riga"], regions: EUROPE, utc: (2.0, 2.0) },
+    Country { code: "LT", names: &["lithuania", "vilnius"], regions: EUROPE, utc: (2.0, 2.0) },
+    Country { code: "RO", names: &["romania", "bucharest"], regions: EUROPE, utc: (2.0, 2.0) },
+    Country { code: "BG", names: &["bulgaria", "sofia"], regions: EUROPE, utc: (2.0, 2.0) },
+    Country { code: "GR", names: &["greece", "athens"], regions: EUROPE, utc: (2.0, 2.0) },
+    Country { code: "UA", names: &["ukraine", "kyiv", "kiev"], regions: EUROPE, utc: (2.0, 2.0) },
+    Country { code: "TR", names: &["turkey", "turkiye", "istanbul"], regions: &["europe", "middle_east", "emea"], utc: (3.0, 3.0) },
+    Country { code: "IL", names: &["israel", "tel aviv"], regions: MIDDLE_EAST, utc: (2.0, 2.0) },
+    Country { code: "AE", names: &["united arab emirates", "uae", "dubai", "abu dhabi"], regions: MIDDLE_EAST, utc: (4.0, 4.0) },
+    Country { code: "SA", names: &["saudi arabia", "riyadh"], regions: MIDDLE_EAST, utc: (3.0, 3.0) },
+    Country { code: "EG", names: &["egypt", "cairo"], regions: &["africa", "middle_east", "emea"], utc: (2.0, 2.0) },
+    Country { code: "ZA", names: &["south africa", "cape town", "johannesburg"], regions: AFRICA, utc: (2.0, 2.0) },
+    Country { code: "NG", names: &["nigeria", "lagos"], regions: AFRICA, utc: (1.0, 1.0) },
+    Country { code: "KE", names: &["kenya", "nairobi"], regions: AFRICA, utc: (3.0, 3.0) },
+    Country { code: "GH", names: &["ghana", "accra"], regions: AFRICA, utc: (0.0, 0.0) },
+    Country { code: "MA", names: &["morocco", "casablanca"], regions: AFRICA, utc: (1.0, 1.0) },
+    Country { code: "IN", names: &["india", "bangalore", "bengaluru", "mumbai", "delhi", "hyderabad", "pune", "chennai"], regions: ASIA, utc: (5.5, 5.5) },
+    Country { code: "LK", names: &["sri lanka", "colombo", "kandy"], regions: ASIA, utc: (5.5, 5.5) },
+    Country { code: "PK", names: &["pakistan", "karachi", "lahore"], regions: ASIA, utc: (5.0, 5.0) },
+    Country { code: "BD", names: &["bangladesh", "dhaka"], regions: ASIA, utc: (6.0, 6.0) },
+    Country { code: "TH", names: &["thailand", "bangkok"], regions: ASIA, utc: (7.0, 7.0) },
+    Country { code: "VN", names: &["vietnam", "viet nam", "hanoi", "ho chi minh city"], regions: ASIA, utc: (7.0, 7.0) },
+    Country { code: "ID", names: &["indonesia", "jakarta", "bali"], regions: ASIA, utc: (7.0, 9.0) },
+    Country { code: "MY", names: &["malaysia", "kuala lumpur"], regions: ASIA, utc: (8.0, 8.0) },
+    Country { code: "SG", names: &["singapore"], regions: ASIA, utc: (8.0, 8.0) },
+    Country { code: "PH", names: &["philippines", "manila"], regions: ASIA, utc: (8.0, 8.0) },
+    Country { code: "CN", names: &["china", "beijing", "shanghai", "shenzhen"], regions: ASIA, utc: (8.0, 8.0) },
+    Country { code: "HK", names: &["hong kong"], regions: ASIA, utc: (8.0, 8.0) },
+    Country { code: "TW", names: &["taiwan", "taipei"], regions: ASIA, utc: (8.0, 8.0) },
+    Country { code: "KR", names: &["south korea", "korea", "seoul"], regions: ASIA, utc: (9.0, 9.0) },
+    Country { code: "JP", names: &["japan", "tokyo"], regions: ASIA, utc: (9.0, 9.0) },
+    Country { code: "AU", names: &["australia", "sydney", "melbourne", "brisbane", "perth"], regions: OCEANIA, utc: (8.0, 10.0) },
+    Country { code: "NZ", names: &["new zealand", "auckland", "wellington"], regions: OCEANIA, utc: (12.0, 12.0) },
+];
+
+// Region names as they appear in postings -> canonical region
+const REGION_NAMES: &[(&str, &str)] = &[
+    ("europe", "europe"), ("european union", "europe"), ("eu", "europe"), ("eea", "europe"),
+    ("emea", "emea"),
+    ("north america", "north_america"), ("northern america", "north_america"),
+    ("latin america", "latam"), ("latam", "latam"), ("south america", "latam"), ("central america", "latam"),
+    ("americas", "americas"), ("the americas", "americas"),
+    ("apac", "apac"), ("asia pacific", "apac"), ("asia", "apac"),
+    ("middle east", "middle_east"), ("mena", "middle_east"),
+    ("africa", "africa"),
+    ("oceania", "oceania"), ("australasia", "oceania"), ("anz", "oceania"),
+];
+
+/// Canonical region keys accepted by `resolve_place` and the `region` filter.
+pub const REGIONS: [&str; 9] = ["europe", "emea", "north_america", "latam", "americas", "apac", "middle_east", "africa", "oceania"];
+
+// Parent regions a job open to the key region is also described by
+const REGION_PARENTS: &[(&str, &[&str])] = &[
+    ("europe", &["emea"]), ("middle_east", &["emea"]), ("africa", &["emea"]),
+    ("north_america", &["americas"]), ("latam", &["americas"]), ("oceania", &["apac"]),
+];
+
+const WORLDWIDE: [&str; 6] = ["worldwide", "anywhere", "global", "globally", "world", "international"];
+
+const TIMEZONE_ABBREVIATIONS: &[(&str, f64)] = &[
+    ("est", -5.0), ("edt", -4.0), ("cst", -6.0), ("cdt", -5.0), ("mst", -7.0), ("mdt", -6.0), ("pst", -8.0), ("pdt", -7.0),
+    ("wet", 0.0), ("bst", 1.0), ("cet", 1.0), ("cest", 2.0), ("eet", 2.0), ("eest", 3.0),
+    ("ist", 5.5), ("sgt", 8.0), ("hkt", 8.0), ("jst", 9.0), ("aest", 10.0), ("aedt", 11.0), ("nzst", 12.0),
+];
+
+/// Lowercase words separated by single spaces and padded, so aliases can be
+/// matched on word boundaries with `contains(" us ")`.
+fn padded_words(text: &str) -> String {
+    let words: Vec<String> = text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(|w| w.to_string())
+        .collect();
+    format!(" {} ", words.join(" "))
+}
+
+fn mentions(padded: &str, name: &str) -> bool {
+    padded.contains(&format!(" {} ", name))
+}
+
+static EXPLICIT_OFFSET: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\b(?:utc|gmt)\s*([+-])\s*(\d{1,2})(?::?(\d{2}))?").unwrap());
+static TOLERANCE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?:\+/-|±|plus or minus)\s*(\d{1,2})\s*(?:h|hours?)").unwrap());
+
+/// Explicit offsets ("UTC-5 to UTC+1", "GMT+2"), zone abbreviations ("CET") and
+/// tolerances ("CET +/- 3 hours") combined into one band.
+fn parse_timezone(text: &str) -> Option<TimezoneBand> {
+    let lower = text.to_lowercase().replace(['−', '–'], "-");
+    let mut offsets: Vec<f64> = vec![];
+
+    for c in EXPLICIT_OFFSET.captures_iter(&lower) {
+        let hours: f64 = c[2].parse().unwrap_or(0.0);
+        let minutes: f64 = c.get(3).and_then(|m| m.as_str().parse().ok()).unwrap_or(0.0);
+        let value = hours + minutes / 60.0;
+        offsets.push(if &c[1] == "-" { -value } else { value });
+    }
+    let padded = padded_words(&lower);
+    for (abbr, offset) in TIMEZONE_ABBREVIATIONS {
+        if mentions(&padded, abbr) { offsets.push(*offset); }
+    }
+    // Bare UTC/GMT only counts when nothing more specific was given
+    if offsets.is_empty() && (mentions(&padded, "utc") || mentions(&padded, "gmt")) {
+        offsets.push(0.0);
+    }
+    if offsets.is_empty() { return None; }
+
+    let spread: f64 = TOLERANCE.captures(&lower).and_then(|c| c[1].parse().ok()).unwrap_or(0.0);
+
+    let min = offsets.iter().cloned().fold(f64::INFINITY, f64::min) - spread;
+    let max = offsets.iter().cloned().fold(f64::NEG_INFINITY, f64::max) + spread;
+    Some(TimezoneBand { min_offset: min, max_offset: max })
+}
+
+/// Normalize a free-form job location ("Europe", "USA Only", "Americas (UTC-5 to UTC+1)",
+/// "Anywhere in the World") into regions, ISO country codes and a UTC offset band.
+pub fn normalize_location(text: &str) -> JobLocation {
+    let padded = padded_words(text);
+    let mut loc = JobLocation {
+        worldwide: WORLDWIDE.iter().any(|w| mentions(&padded, w)),
+        ..Default::default()
+    };
+    // Country names come out first so "South Africa" doesn't also read as the region "Africa"
+    let mut rest = padded.clone();
+    for country in COUNTRIES {
+        for name in country.names.iter().filter(|n| mentions(&padded, n)) {
+            if !loc.countries.iter().any(|c| c == country.code) {
+                loc.countries.push(country.code.to_string());
+            }
+            rest = rest.replace(&format!(" {} ", name), " ");
+        }
+    }
+    for (name, region) in REGION_NAMES {
+        if mentions(&rest, name) && !loc.regions.iter().any(|r| r == region) {
+            loc.regions.push(region.to_string());
+        }
+    }
+    loc.timezone = parse_timezone(text);
+    loc
+}
+
+/// The job's normalized location, computed on the fly for jobs cached before
+/// locations were normalized at ingestion.
+pub fn job_location(job: &Job) -> JobLocation {
+    job.location_info.clone().unwrap_or_else(|| normalize_location(job.location.as_deref().unwrap_or("")))
+}
+
+/// A user's (or a filter's) whereabouts: a country with its regions and offsets,
+/// or just a region.
+#[derive(Debug, Clone)]
+pub struct Place {
+    pub label: String,
+    pub country: Option<&'static str>,
+    pub regions: Vec<String>,
+    pub utc: Option<(f64, f64)>,
+}
+
+/// Resolve free text like "Colombo, Sri Lanka", "DE" or "Europe" to a place.
+pub fn resolve_place(text: &str) -> Option<Place> {
+    let trimmed = text.trim();
+    if trimmed.is_empty() { return None; }
+    let padded = padded_words(trimmed);
+    let country = COUNTRIES.iter()
+        .find(|c| c.code.eq_ignore_ascii_case(trimmed))
+        .or_else(|| COUNTRIES.iter().find(|c| c.names.iter().any(|n| mentions(&padded, n))));
+    if let Some(c) = country {
+        return Some(Place {
+            label: trimmed.to_string(),
+            country: Some(c.code),
+            regions: c.regions.iter().map(|r| r.to_string()).collect(),
+            utc: Some(c.utc),
+        });
+    }
+    let region = REGION_NAMES.iter()
+        .find(|(name, canonical)| mentions(&padded, name) || trimmed.eq_ignore_ascii_case(canonical))
+        .map(|(_, canonical)| *canonical)?;
+    let mut regions = vec![region.to_string()];
+    if let Some((_, parents)) = REGION_PARENTS.iter().find(|(r, _)| *r == region) {
+        regions.extend(parents.iter().map(|p| p.to_string()));
+    }
+    Some(Place { label: trimmed.to_string(), country: None, regions, utc: None })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Eligibility {
+    Eligible,
+    Ineligible,
+    // The posting doesn't say enough to decide
+    Unknown,
+}
+
+/// Whether someone at `place` may apply. Named countries and regions decide when
+/// the posting has them; otherwise the timezone band is compared with the place's
+/// UTC offsets.
+pub fn eligibility(job: &JobLocation, place: &Place) -> Eligibility {
+    if job.worldwide { return Eligibility::Eligible; }
+    if !job.countries.is_empty() || !job.regions.is_empty() {
+        let by_country = place.country.map(|c| job.countries.iter().any(|jc| jc == c)).unwrap_or(false);
+        let by_region = job.regions.iter().any(|r| place.regions.contains(r))
+            // A region-only place (a filter) also covers jobs naming countries inside it
+            || place.country.is_none() && job.countries.iter().any(|code| {
+                COUNTRIES.iter().any(|c| c.code == code && place.regions.first().map(|r| c.regions.contains(&r.as_str())).unwrap_or(false))
+            });
+        return if by_country || by_region { Eligibility::Eligible } else { Eligibility::Ineligible };
+    }
+    match (job.timezone, place.utc) {
+        (Some(band), Some((lo, hi))) => {
+            if lo <= band.max_offset && hi >= band.min_offset { Eligibility::Eligible } else { Eligibility::Ineligible }
+        }
+        _ => Eligibility::Unknown,
+    }
+}
@@ -0,0 +1,59 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
+use chrono::Utc;
+use uuid::Uuid;
+
+pub struct OutgoingMail {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+/// Outbound email. `MAIL_WEBHOOK_URL` sends each message as JSON (`from`, `to`, `subject`,
+/// `text`) to an HTTP mail relay; otherwise messages are written as `.eml` files to
+/// `MAIL_SPOOL_DIR` (default `./data/mail_spool`), which is what tests and local runs read.
+pub enum Mailer {
+    Spool { dir: PathBuf, from: String },
+    Webhook { url: String, from: String },
+}
+
+impl Mailer {
+    pub fn from_env() -> Mailer {
+        let from = std::env::var("MAIL_FROM").unwrap_or_else(|_| "JobLens <no-reply@joblens.app>".to_string());
+        match std::env::var("MAIL_WEBHOOK_URL").ok().filter(|u| !u.trim().is_empty()) {
+            Some(url) => Mailer::Webhook { url, from },
+            None => Mailer::Spool {
+                dir: std::env::var("MAIL_SPOOL_DIR").map(PathBuf::from).unwrap_or_else(|_| PathBuf::from("./data/mail_spool")),
+                from,
+            },
+        }
+    }
+
+    pub async fn send(&self, mail: &OutgoingMail) -> Result<(), String> {
+        match self {
+            Mailer::Spool { dir, from } => {
+                std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create mail spool: {}", e))?;
+                let now = Utc::now();
+                let path = dir.join(format!("{}-{}.eml", now.format("%Y%m%dT%H%M%S%.3f"), Uuid::new_v4()));
+                let message = format!(
+                    "From: {}\r\nTo: {}\r\nSubject: {}\r\nDate: {}\r\nContent-Type: text/plain; charset=utf-8\r\n\r\n{}\r\n",
+                    from, mail.to, mail.subject, now.to_rfc2822(), mail.body.replace('\n', "\r\n")
+                );
+                std::fs::write(&path, message).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+            }
+            Mailer::Webhook { url, from } => {
+                let response = reqwest::Client::builder()
+                    .timeout(Duration::from_secs(15))
+                    .build()
+                    .map_err(|e| format!("Failed to create HTTP client: {}", e))?
+                    .post(url)
+                    .json(&serde_json::json!({ "from": from, "to": mail.to, "subject": mail.subject, "text": mail.body }))
+                    .send()
+                    .await
+                    .map_err(|e| format!("Failed to send mail: {}", e))?;
+                if response.status().is_success() { Ok(()) } else { Err(format!("Mail relay returned HTTP {}", response.status())) }
+            }
+        }
+    }
+}
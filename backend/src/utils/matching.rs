@@ -1,7 +1,14 @@
 use std::collections::{HashMap, HashSet};
 use crate::utils::text::{normalize_token, tokenize};
 
-pub fn compute_match(user_skills: &[String], job_text: &str) -> (f64, Vec<String>, Vec<String>) {
+// Common tech skills used by the keyword-based fallbacks when no user skills are known
+pub const COMMON_TECH_SKILLS: [&str; 23] = [
+    "rust", "python", "javascript", "typescript", "react", "node.js", "docker",
+    "kubernetes", "aws", "gcp", "sql", "mongodb", "redis", "git", "linux",
+    "java", "c++", "go", "html", "css", "angular", "vue", "express"
+];
+
+fn compute_match(user_skills: &[String], job_tokens: &HashSet<String>) -> (f64, Vec<String>, Vec<String>) {
     let user_set: HashSet<String> = user_skills
         .iter()
         .map(|s| normalize_token(s))
@@ -12,15 +19,13 @@ pub fn compute_match(user_skills: &[String], job_text: &str) -> (f64, Vec<String
         return (0.0, vec![], vec![]);
     }
 
-    let job_tokens: HashSet<String> = tokenize(job_text).into_iter().collect();
-
     let matching: Vec<String> = user_set
-        .intersection(&job_tokens)
+        .intersection(job_tokens)
         .cloned()
         .collect();
 
     let missing: Vec<String> = user_set
-        .difference(&job_tokens)
+        .difference(job_tokens)
         .cloned()
         .collect();
 
@@ -29,8 +34,14 @@ pub fn compute_match(user_skills: &[String], job_text: &str) -> (f64, Vec<String
 }
 
 pub fn compute_weighted_match(user_skills: &[String], job_text: &str, idf: &HashMap<String, f64>) -> (f64, Vec<String>, Vec<String>) {
+    let job_tokens: HashSet<String> = tokenize(job_text).into_iter().collect();
+    weighted_match_tokens(user_skills, &job_tokens, idf)
+}
+
+/// `compute_weighted_match` against an already tokenized job, for scoring many users against the same jobs.
+pub fn weighted_match_tokens(user_skills: &[String], job_tokens: &HashSet<String>, idf: &HashMap<String, f64>) -> (f64, Vec<String>, Vec<String>) {
     if idf.is_empty() {
-        return compute_match(user_skills, job_text);
+        return compute_match(user_skills, job_tokens);
     }
     let user_tokens: Vec<String> = user_skills
         .iter()
@@ -39,8 +50,6 @@ pub fn compute_weighted_match(user_skills: &[String], job_text: &str, idf: &Hash
         .collect();
     if user_tokens.is_empty() { return (0.0, vec![], vec![]); }
 
-    let job_tokens: HashSet<String> = tokenize(job_text).into_iter().collect();
-
     let mut matching: Vec<String> = vec![];
     let mut missing: Vec<String> = vec![];
     let mut match_sum = 0.0;
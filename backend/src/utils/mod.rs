@@ -1,4 +1,18 @@
 pub mod jwt;
 pub mod text;
 pub mod matching;
-
+pub mod uploads;
+pub mod insights;
+pub mod stats;
+pub mod ranking;
+pub mod catalog;
+pub mod ids;
+pub mod dedup;
+pub mod salary;
+pub mod location;
+pub mod search;
+pub mod facets;
+pub mod auth;
+pub mod sessions;
+pub mod google;
+pub mod mail;
@@ -0,0 +1,131 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::models::{FeedbackType, Job, ScoreAdjustment, UserFeedback};
+use crate::utils::text::tokenize;
+
+const LIKED_FROM: i32 = 4;
+const DISMISSED_UP_TO: i32 = 2;
+const CATEGORY_STEP: f64 = 0.05;
+const COMPANY_STEP: f64 = 0.1;
+const MAX_GROUP_DELTA: f64 = 0.2;
+const SIMILARITY_THRESHOLD: f64 = 0.25;
+const MAX_SIMILARITY_PENALTY: f64 = 0.5;
+
+struct DismissedJob {
+    title: String,
+    title_tokens: HashSet<String>,
+    body_tokens: HashSet<String>,
+}
+
+/// What a user's recommendation ratings say about their taste: net likes per
+/// category and company, plus the jobs they dismissed.
+#[derive(Default)]
+pub struct FeedbackProfile {
+    categories: HashMap<String, i32>,
+    companies: HashMap<String, i32>,
+    dismissed: Vec<DismissedJob>,
+    dismissed_ids: HashSet<String>,
+}
+
+fn jaccard(a: &HashSet<String>, b: &HashSet<String>) -> f64 {
+    let union = a.union(b).count();
+    if union == 0 { return 0.0; }
+    a.intersection(b).count() as f64 / union as f64
+}
+
+fn meta_str(fb: &UserFeedback, key: &str) -> Option<String> {
+    fb.metadata.as_ref()?.get(key)?.as_str().map(|s| s.to_string())
+}
+
+impl FeedbackProfile {
+    /// Rated jobs are looked up in `jobs`; when a job has left the catalog the
+    /// title/company/category the client sent in `metadata` are used instead.
+    pub fn build(feedback: &[UserFeedback], jobs: &[Job]) -> FeedbackProfile {
+        let mut profile = FeedbackProfile::default();
+        for fb in feedback.iter().filter(|f| f.feedback_type == FeedbackType::JobRecommendation) {
+            let sign = if fb.rating >= LIKED_FROM { 1 } else if fb.rating <= DISMISSED_UP_TO { -1 } else { continue };
+            let job = jobs.iter().find(|j| j.id == fb.job_id || j.external_id.as_deref() == Some(fb.job_id.as_str()));
+            let title = job.map(|j| j.title.clone()).or_else(|| meta_str(fb, "title"));
+            let company = job.map(|j| j.company.clone()).or_else(|| meta_str(fb, "company"));
+            let category = job.and_then(|j| j.category.clone()).or_else(|| meta_str(fb, "category"));
+
+            if let Some(c) = category { *profile.categories.entry(c.to_lowercase()).or_insert(0) += sign; }
+            if let Some(c) = company { *profile.companies.entry(c.to_lowercase()).or_insert(0) += sign; }
+            if sign < 0 {
+                profile.dismissed_ids.insert(fb.job_id.clone());
+                if let Some(t) = title {
+                    profile.dismissed.push(DismissedJob {
+                        title_tokens: tokenize(&t).into_iter().collect(),
+                        body_tokens: job.map(|j| tokenize(&j.description).into_iter().collect()).unwrap_or_default(),
+                        title: t,
+                    });
+                }
+            }
+        }
+        profile
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.categories.is_empty() && self.companies.is_empty() && self.dismissed.is_empty()
+    }
+
+    pub fn is_dismissed(&self, job: &Job) -> bool {
+        self.dismissed_ids.contains(&job.id) || job.external_id.as_ref().map(|e| self.dismissed_ids.contains(e)).unwrap_or(false)
+    }
+
+    /// Multiplier for the base match score and the factors that produced it.
+    pub fn adjust(&self, job: &Job) -> (f64, Vec<ScoreAdjustment>) {
+        let mut adjustments: Vec<ScoreAdjustment> = vec![];
+
+        if let Some(cat) = &job.category {
+            let net = *self.categories.get(&cat.to_lowercase()).unwrap_or(&0);
+            if net != 0 {
+                adjustments.push(ScoreAdjustment {
+                    factor: "category".into(),
+                    delta: (net as f64 * CATEGORY_STEP).clamp(-MAX_GROUP_DELTA, MAX_GROUP_DELTA),
+                    reason: format!("{} {} job(s) in {}", if net > 0 { "Liked" } else { "Dismissed" }, net.abs(), cat),
+                });
+            }
+        }
+
+        let net = *self.companies.get(&job.company.to_lowercase()).unwrap_or(&0);
+        if net != 0 {
+            adjustments.push(ScoreAdjustment {
+                factor: "company".into(),
+                delta: (net as f64 * COMPANY_STEP).clamp(-MAX_GROUP_DELTA, MAX_GROUP_DELTA),
+                reason: format!("{} {} job(s) at {}", if net > 0 { "Liked" } else { "Dismissed" }, net.abs(), job.company),
+            });
+        }
+
+        // Titles dominate similarity; descriptions share a lot of boilerplate
+        let title_tokens: HashSet<String> = tokenize(&job.title).into_iter().collect();
+        let body_tokens: HashSet<String> = if self.dismissed.is_empty() { HashSet::new() } else { tokenize(&job.description).into_iter().collect() };
+        let closest = self.dismissed.iter()
+            .map(|d| {
+                let body = if d.body_tokens.is_empty() { 0.0 } else { jaccard(&body_tokens, &d.body_tokens) };
+                let sim = if d.body_tokens.is_empty() { jaccard(&title_tokens, &d.title_tokens) } else { 0.7 * jaccard(&title_tokens, &d.title_tokens) + 0.3 * body };
+                (sim, d)
+            })
+            .max_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+        if let Some((sim, d)) = closest {
+            if sim >= SIMILARITY_THRESHOLD {
+                adjustments.push(ScoreAdjustment {
+                    factor: "similar_to_dismissed".into(),
+                    delta: -(sim * MAX_SIMILARITY_PENALTY),
+                    reason: format!("Similar to dismissed \"{}\"", d.title),
+                });
+            }
+        }
+
+        let multiplier = (1.0 + adjustments.iter().map(|a| a.delta).sum::<f64>()).max(0.0);
+        (multiplier, adjustments)
+    }
+}
+
+pub fn describe_adjustments(base_score: f64, adjustments: &[ScoreAdjustment]) -> String {
+    let mut parts = vec![format!("Weighted skill overlap {}", base_score as i32)];
+    for a in adjustments.iter() {
+        parts.push(format!("{:+.0}% {}", a.delta * 100.0, a.reason));
+    }
+    parts.join("; ")
+}
@@ -0,0 +1,175 @@
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+use crate::models::{SalaryPeriod, SalaryRange};
+
+const CURRENCY_CODES: [&str; 17] = [
+    "usd", "eur", "gbp", "cad", "aud", "nzd", "chf", "inr", "jpy", "sek", "nok", "dkk", "pln", "brl", "mxn", "sgd", "zar",
+];
+
+// Prefixed dollar variants come before the bare "$"
+const CURRENCY_SYMBOLS: [(&str, &str); 11] = [
+    ("ca$", "CAD"), ("c$", "CAD"), ("au$", "AUD"), ("a$", "AUD"), ("nz$", "NZD"), ("s$", "SGD"), ("r$", "BRL"),
+    ("$", "USD"), ("€", "EUR"), ("£", "GBP"), ("₹", "INR"),
+];
+
+// "per hour", "an hour", "/hr": the period an amount is quoted in
+static EXPLICIT_PERIOD: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?:\bper\s+|\ban?\s+)(hour|day|week|month|year|annum)\b|/\s*(hours?|hr|h|days?|d|weeks?|wk|w|months?|mo|m|years?|yr|y)\b").unwrap()
+});
+// Standalone period words, whole words only ("Monday" or "today" aren't days)
+static PERIOD_WORD: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\b(hourly|hour|hr|daily|day|weekly|week|wk|monthly|month|pcm|yearly|year|annual|annually|annum|pa|p\.a)\b").unwrap()
+});
+static CURRENCY_CODE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\b[a-z]{3}\b").unwrap());
+static AMOUNT: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(\d{1,3}(?:[,.\x{a0} ]\d{2,3})*[,.\x{a0} ]\d{3}(?:[.,]\d{1,2})?|\d+(?:\.\d+)?)\s?(k|m)?\b").unwrap()
+});
+// "40 hours", "3 years": a count of something, not money
+static QUANTITY_UNIT: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^\s*(?:hours|hrs|days|weeks|months|years|yrs)\b").unwrap());
+
+// Without an explicit period, amounts this small can only be hourly rates
+const HOURLY_CEILING: f64 = 300.0;
+
+fn detect_currency(lower: &str) -> Option<String> {
+    if let Some(code) = CURRENCY_CODE.find_iter(lower).map(|m| m.as_str()).find(|c| CURRENCY_CODES.contains(c)) {
+        return Some(code.to_uppercase());
+    }
+    CURRENCY_SYMBOLS.iter().find(|(sym, _)| lower.contains(sym)).map(|(_, code)| code.to_string())
+}
+
+fn period_named(word: &str) -> Option<SalaryPeriod> {
+    match word {
+        "hourly" | "hour" | "hours" | "hr" | "h" => Some(SalaryPeriod::Hour),
+        "daily" | "day" | "days" | "d" => Some(SalaryPeriod::Day),
+        "weekly" | "week" | "weeks" | "wk" | "w" => Some(SalaryPeriod::Week),
+        "monthly" | "month" | "months" | "mo" | "m" | "pcm" => Some(SalaryPeriod::Month),
+        "yearly" | "year" | "years" | "yr" | "y" | "annual" | "annually" | "annum" | "pa" | "p.a" => Some(SalaryPeriod::Year),
+        _ => None,
+    }
+}
+
+/// An explicit "per X" or "/X" wins over a bare period word, and the first one wins, so
+/// "$120k/year, 40 hours/week" is yearly.
+fn detect_period(lower: &str) -> Option<SalaryPeriod> {
+    EXPLICIT_PERIOD.captures(lower)
+        .and_then(|c| c.get(1).or(c.get(2)))
+        .or_else(|| PERIOD_WORD.find(lower))
+        .and_then(|m| period_named(m.as_str()))
+}
+
+/// Amounts in order of appearance. Digit grouping (`120,000`, `120.000`, `120 000`,
+/// `12,00,000`), decimal parts and `k`/`m` suffixes are resolved; in "80-120k" the suffix of the
+/// upper bound also applies to the bare lower one. Counts such as "40 hours" are skipped.
+fn amounts(lower: &str) -> Vec<f64> {
+    let mut found: Vec<(f64, bool)> = AMOUNT.captures_iter(lower)
+        .filter(|c| !QUANTITY_UNIT.is_match(&lower[c.get(0).unwrap().end()..]))
+        .filter_map(|c| {
+            let raw = c[1].replace([' ', '\u{a0}'], "");
+            // A separator followed by one or two digits is the decimal point; every other one groups digits
+            let digits = match raw.rfind([',', '.']) {
+                Some(i) if raw.len() - i - 1 <= 2 => format!("{}.{}", raw[..i].replace([',', '.'], ""), &raw[i + 1..]),
+                _ => raw.replace([',', '.'], ""),
+            };
+            let value: f64 = digits.parse().ok()?;
+            let multiplier = match c.get(2).map(|m| m.as_str()) { Some("k") => 1_000.0, Some("m") => 1_000_000.0, _ => 1.0 };
+            Some((value * multiplier, multiplier > 1.0))
+        })
+        .filter(|(v, _)| *v > 0.0)
+        .collect();
+    if found.len() >= 2 && !found[0].1 && found[1].1 && found[0].0 < 1_000.0 {
+        let scale = if found[1].0 >= 1_000_000.0 { 1_000_000.0 } else { 1_000.0 };
+        found[0].0 *= scale;
+    }
+    found.into_iter().map(|(v, _)| v).collect()
+}
+
+/// Parse free-form salary text such as "$80k-$120k", "€45,000 per year",
+/// "Up to 60 USD/hour" or "from £50k". Returns None for text without an amount
+/// ("Competitive", "DOE", empty strings).
+pub fn parse_salary(text: &str) -> Option<SalaryRange> {
+    let lower = text.trim().to_lowercase();
+    if lower.is_empty() { return None; }
+
+    let values = amounts(&lower);
+    let (min, max) = match values.as_slice() {
+        [] => return None,
+        [only] => {
+            if ["up to", "max", "under", "below", "less than"].iter().any(|w| lower.contains(w)) {
+                (None, Some(*only))
+            } else if ["from", "starting", "min", "at least", "+"].iter().any(|w| lower.contains(w)) {
+                (Some(*only), None)
+            } else {
+                (Some(*only), Some(*only))
+            }
+        }
+        [a, b, ..] => (Some(a.min(*b)), Some(a.max(*b))),
+    };
+
+    let top = max.or(min).unwrap_or(0.0);
+    let period = detect_period(&lower).unwrap_or(if top < HOURLY_CEILING { SalaryPeriod::Hour } else { SalaryPeriod::Year });
+
+    Some(SalaryRange { min, max, currency: detect_currency(&lower), period })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use SalaryPeriod::*;
+
+    #[test]
+    fn detects_the_quoted_period() {
+        let cases: [(&str, Option<SalaryPeriod>); 16] = [
+            ("$120k/year, 40 hours/week", Some(Year)),
+            ("$45 per hour", Some(Hour)),
+            ("$45 an hour", Some(Hour)),
+            ("£400/day", Some(Day)),
+            ("€3,500 / month", Some(Month)),
+            ("$2,000/wk", Some(Week)),
+            ("60 USD/hr", Some(Hour)),
+            ("£50,000 per annum", Some(Year)),
+            ("£4,000 pcm", Some(Month)),
+            ("€60k p.a.", Some(Year)),
+            ("Hourly rate of $80", Some(Hour)),
+            ("Annual salary $90k, 25 days holiday", Some(Year)),
+            // Whole words only
+            ("$95k, paid Monday to Friday", None),
+            ("$95k, apply today", None),
+            ("$95k plus holiday allowance", None),
+            ("$150k, flexible hours", None),
+        ];
+        for (text, expected) in cases {
+            assert_eq!(detect_period(&text.to_lowercase()), expected, "{}", text);
+        }
+    }
+
+    #[test]
+    fn parses_ranges() {
+        type Case = (&'static str, Option<f64>, Option<f64>, Option<&'static str>, SalaryPeriod);
+        let cases: [Case; 10] = [
+            ("$80k-$120k", Some(80_000.0), Some(120_000.0), Some("USD"), Year),
+            ("80-120k EUR", Some(80_000.0), Some(120_000.0), Some("EUR"), Year),
+            ("USD 110,000 - 130,000", Some(110_000.0), Some(130_000.0), Some("USD"), Year),
+            ("€45.000 per year", Some(45_000.0), Some(45_000.0), Some("EUR"), Year),
+            ("₹12,00,000", Some(1_200_000.0), Some(1_200_000.0), Some("INR"), Year),
+            ("Up to 60 USD/hour", None, Some(60.0), Some("USD"), Hour),
+            ("from £50k", Some(50_000.0), None, Some("GBP"), Year),
+            ("CA$95,000 - CA$105,000", Some(95_000.0), Some(105_000.0), Some("CAD"), Year),
+            ("$120k/year, 40 hours/week", Some(120_000.0), Some(120_000.0), Some("USD"), Year),
+            // No period given: too small to be anything but hourly
+            ("$35 - $45", Some(35.0), Some(45.0), Some("USD"), Hour),
+        ];
+        for (text, min, max, currency, period) in cases {
+            let range = parse_salary(text).unwrap_or_else(|| panic!("{} didn't parse", text));
+            assert_eq!((range.min, range.max, range.currency.as_deref(), range.period), (min, max, currency, period), "{}", text);
+        }
+    }
+
+    #[test]
+    fn text_without_an_amount_is_not_a_salary() {
+        for text in ["", "  ", "Competitive", "DOE"] {
+            assert_eq!(parse_salary(text), None, "{}", text);
+        }
+    }
+}
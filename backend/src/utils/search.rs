@@ -0,0 +1,380 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use crate::models::Job;
+use crate::utils::text::{strip_html, tokenize};
+
+// BM25 parameters
+const K1: f64 = 1.2;
+const B: f64 = 0.75;
+// Words that only share a prefix with a query term count for less than exact hits
+const PREFIX_WEIGHT: f64 = 0.5;
+const MIN_PREFIX_LEN: usize = 2;
+const PHRASE_BONUS: f64 = 1.5;
+const SNIPPET_WORDS: usize = 30;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Field {
+    Title,
+    Company,
+    Category,
+    Description,
+}
+
+impl Field {
+    pub const ALL: [Field; 4] = [Field::Title, Field::Company, Field::Category, Field::Description];
+
+    pub fn boost(&self) -> f64 {
+        match self {
+            Field::Title => 3.0,
+            Field::Company => 2.0,
+            Field::Category => 1.5,
+            Field::Description => 1.0,
+        }
+    }
+
+    fn index(&self) -> usize {
+        match self {
+            Field::Title => 0,
+            Field::Company => 1,
+            Field::Category => 2,
+            Field::Description => 3,
+        }
+    }
+}
+
+struct Posting {
+    doc: usize,
+    field: Field,
+    positions: Vec<u32>,
+}
+
+/// What a query asks for: single terms (each also matching as a prefix, or only as a
+/// prefix when written `term*`) and quoted phrases. Every clause has to match.
+#[derive(Debug, Default)]
+pub struct ParsedQuery {
+    pub terms: Vec<(String, bool)>,
+    pub phrases: Vec<Vec<String>>,
+}
+
+pub fn parse_query(query: &str) -> ParsedQuery {
+    let mut parsed = ParsedQuery::default();
+    for (i, part) in query.split('"').enumerate() {
+        if i % 2 == 1 {
+            let tokens = tokenize(part);
+            match tokens.len() {
+                0 => {}
+                1 => parsed.terms.push((tokens[0].clone(), false)),
+                _ => parsed.phrases.push(tokens),
+            }
+            continue;
+        }
+        for word in part.split_whitespace() {
+            let explicit_prefix = word.ends_with('*');
+            for token in tokenize(word.trim_end_matches('*')) {
+                parsed.terms.push((token, explicit_prefix));
+            }
+        }
+    }
+    parsed
+}
+
+pub struct SearchResults {
+    // (job id, score), best first
+    pub hits: Vec<(String, f64)>,
+    // Index words the query matched, for highlighting
+    pub matched_terms: HashSet<String>,
+}
+
+/// Inverted index over the job catalog with per-field positions, rebuilt after every sync.
+#[derive(Default)]
+pub struct SearchIndex {
+    job_ids: Vec<String>,
+    field_lens: Vec<[u32; 4]>,
+    avg_lens: [f64; 4],
+    postings: BTreeMap<String, Vec<Posting>>,
+}
+
+impl SearchIndex {
+    pub fn build(jobs: &[Job]) -> Self {
+        let mut index = SearchIndex::default();
+        let mut totals = [0u64; 4];
+        for (doc, job) in jobs.iter().enumerate() {
+            let mut lens = [0u32; 4];
+            for field in Field::ALL {
+                let text = match field {
+                    Field::Title => job.title.clone(),
+                    Field::Company => job.company.clone(),
+                    Field::Category => job.category.clone().unwrap_or_default(),
+                    Field::Description => strip_html(&job.description),
+                };
+                let tokens = tokenize(&text);
+                lens[field.index()] = tokens.len() as u32;
+                totals[field.index()] += tokens.len() as u64;
+
+                let mut positions: HashMap<String, Vec<u32>> = HashMap::new();
+                for (pos, token) in tokens.into_iter().enumerate() {
+                    positions.entry(token).or_default().push(pos as u32);
+                }
+                for (term, positions) in positions {
+                    index.postings.entry(term).or_default().push(Posting { doc, field, positions });
+                }
+            }
+            index.job_ids.push(job.id.clone());
+            index.field_lens.push(lens);
+        }
+        if !jobs.is_empty() {
+            for (i, total) in totals.iter().enumerate() {
+                index.avg_lens[i] = (*total as f64 / jobs.len() as f64).max(1.0);
+            }
+        }
+        index
+    }
+
+    fn idf(&self, postings: &[Posting]) -> f64 {
+        let df = postings.iter().map(|p| p.doc).collect::<HashSet<_>>().len() as f64;
+        let n = self.job_ids.len() as f64;
+        (1.0 + (n - df + 0.5) / (df + 0.5)).ln()
+    }
+
+    fn bm25(&self, posting: &Posting, idf: f64) -> f64 {
+        let f = posting.field.index();
+        let tf = posting.positions.len() as f64;
+        let len = self.field_lens[posting.doc][f] as f64;
+        let norm = 1.0 - B + B * len / self.avg_lens[f];
+        posting.field.boost() * idf * tf * (K1 + 1.0) / (tf + K1 * norm)
+    }
+
+    /// Index words a query term stands for, with their weight: the term itself and,
+    /// as prefix matches, longer words starting with it.
+    fn expand(&self, term: &str, prefix_only: bool) -> Vec<(&String, f64)> {
+        let mut out = vec![];
+        if !prefix_only {
+            if let Some((word, _)) = self.postings.get_key_value(term) { out.push((word, 1.0)); }
+        }
+        if term.chars().count() >= MIN_PREFIX_LEN {
+            let weight = if prefix_only { 1.0 } else { PREFIX_WEIGHT };
+            for (word, _) in self.postings.range(term.to_string()..).take_while(|(w, _)| w.starts_with(term)) {
+                // `term*` matches the term itself too; otherwise that was counted above
+                if word != term || prefix_only { out.push((word, weight)); }
+            }
+        }
+        out
+    }
+
+    pub fn search(&self, query: &str) -> SearchResults {
+        let parsed = parse_query(query);
+        let mut scores: HashMap<usize, f64> = HashMap::new();
+        let mut candidates: Option<HashSet<usize>> = None;
+        let mut matched_terms: HashSet<String> = HashSet::new();
+        let narrow = |docs: HashSet<usize>, candidates: &mut Option<HashSet<usize>>| {
+            *candidates = Some(match candidates.take() {
+                Some(c) => c.intersection(&docs).copied().collect(),
+                None => docs,
+            });
+        };
+
+        for (term, prefix_only) in parsed.terms.iter() {
+            let mut docs: HashSet<usize> = HashSet::new();
+            for (word, weight) in self.expand(term, *prefix_only) {
+                let postings = &self.postings[word];
+                let idf = self.idf(postings);
+                for p in postings.iter() {
+                    *scores.entry(p.doc).or_insert(0.0) += weight * self.bm25(p, idf);
+                    docs.insert(p.doc);
+                }
+                matched_terms.insert(word.clone());
+            }
+            narrow(docs, &mut candidates);
+        }
+
+        for phrase in parsed.phrases.iter() {
+            let lists: Option<Vec<&Vec<Posting>>> = phrase.iter().map(|t| self.postings.get(t)).collect();
+            let lists = match lists {
+                Some(l) => l,
+                None => { narrow(HashSet::new(), &mut candidates); continue; }
+            };
+            // Positions of every phrase word, per (doc, field)
+            let by_doc: Vec<HashMap<(usize, Field), &Vec<u32>>> = lists.iter()
+                .map(|postings| postings.iter().map(|p| ((p.doc, p.field), &p.positions)).collect())
+                .collect();
+            let mut docs: HashSet<usize> = HashSet::new();
+            for first in lists[0].iter() {
+                let key = (first.doc, first.field);
+                let hit = first.positions.iter().any(|&start| {
+                    by_doc.iter().enumerate().skip(1).all(|(offset, m)| {
+                        m.get(&key).map(|ps| ps.contains(&(start + offset as u32))).unwrap_or(false)
+                    })
+                });
+                if hit {
+                    let score: f64 = lists.iter()
+                        .filter_map(|postings| {
+                            let idf = self.idf(postings);
+                            postings.iter().find(|p| p.doc == first.doc && p.field == first.field).map(|p| self.bm25(p, idf))
+                        })
+                        .sum();
+                    *scores.entry(first.doc).or_insert(0.0) += PHRASE_BONUS * score;
+                    docs.insert(first.doc);
+                }
+            }
+            if !docs.is_empty() { matched_terms.extend(phrase.iter().cloned()); }
+            narrow(docs, &mut candidates);
+        }
+
+        let mut hits: Vec<(String, f64)> = candidates.unwrap_or_default().into_iter()
+            .map(|doc| (self.job_ids[doc].clone(), scores.get(&doc).copied().unwrap_or(0.0)))
+            .collect();
+        hits.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal).then_with(|| a.0.cmp(&b.0)));
+        SearchResults { hits, matched_terms }
+    }
+}
+
+fn word_matches(word: &str, terms: &HashSet<String>) -> bool {
+    tokenize(word).iter().any(|t| terms.contains(t))
+}
+
+fn escape_html(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Wrap every word of `text` that matched the query in `<mark>` tags. The text comes
+/// from job feeds, so it is HTML-escaped and the marks are the only markup.
+pub fn highlight(text: &str, terms: &HashSet<String>) -> String {
+    text.split_whitespace()
+        .map(|w| if word_matches(w, terms) { format!("<mark>{}</mark>", escape_html(w)) } else { escape_html(w) })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// The stretch of `text` with the most matched words, highlighted; the opening
+/// words when nothing matched.
+pub fn snippet(text: &str, terms: &HashSet<String>) -> String {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    if words.is_empty() { return String::new(); }
+    let hits: Vec<bool> = words.iter().map(|w| word_matches(w, terms)).collect();
+
+    let window = SNIPPET_WORDS.min(words.len());
+    let mut count = hits[..window].iter().filter(|h| **h).count();
+    let (mut best_start, mut best_count) = (0, count);
+    for start in 1..=(words.len() - window) {
+        if hits[start - 1] { count -= 1; }
+        if hits[start + window - 1] { count += 1; }
+        if count > best_count { best_start = start; best_count = count; }
+    }
+
+    let mut out = highlight(&words[best_start..best_start + window].join(" "), terms);
+    if best_start > 0 { out = format!("… {}", out); }
+    if best_start + window < words.len() { out.push_str(" …"); }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sources::new_job;
+
+    fn job(id: &str, title: &str, company: &str, description: &str) -> Job {
+        let mut job = new_job("test", id, title.into(), company.into(), format!("https://jobs.test/{}", id), description.into());
+        job.id = id.into();
+        job
+    }
+
+    fn ids(index: &SearchIndex, query: &str) -> Vec<String> {
+        index.search(query).hits.into_iter().map(|(id, _)| id).collect()
+    }
+
+    fn terms(words: &[&str]) -> HashSet<String> {
+        words.iter().map(|w| w.to_string()).collect()
+    }
+
+    #[test]
+    fn parse_query_splits_terms_prefixes_and_phrases() {
+        let parsed = parse_query(r#"Rust back* "Machine  Learning" "remote" """#);
+        assert_eq!(parsed.terms, vec![("rust".to_string(), false), ("back".to_string(), true), ("remote".to_string(), false)]);
+        assert_eq!(parsed.phrases, vec![vec!["machine".to_string(), "learning".to_string()]]);
+
+        // An unclosed quote still reads as a phrase
+        assert_eq!(parse_query(r#"senior "data engineer"#).phrases, vec![vec!["data".to_string(), "engineer".to_string()]]);
+        assert!(parse_query("  ").terms.is_empty());
+    }
+
+    #[test]
+    fn bm25_ranks_boosted_fields_and_frequent_terms_first() {
+        let index = SearchIndex::build(&[
+            job("desc", "Engineer", "Acme", "We use rust for tooling"),
+            job("title", "Rust Engineer", "Initech", "Backend services"),
+            job("often", "Engineer", "Globex", "rust rust rust everywhere in rust"),
+            job("none", "Designer", "Umbrella", "Figma"),
+        ]);
+        assert_eq!(ids(&index, "rust"), vec!["title", "often", "desc"]);
+        // Every term has to match
+        assert_eq!(ids(&index, "rust backend"), vec!["title"]);
+        assert!(ids(&index, "rust figma").is_empty());
+    }
+
+    #[test]
+    fn phrases_match_adjacent_words_in_one_field() {
+        let index = SearchIndex::build(&[
+            job("phrase", "Machine Learning Engineer", "Acme", "Models"),
+            job("apart", "Machine Operator", "Initech", "Learning on the job"),
+            job("reversed", "Learning Machine", "Globex", ""),
+        ]);
+        assert_eq!(ids(&index, r#""machine learning""#), vec!["phrase"]);
+        assert!(ids(&index, r#""learning engineer machine""#).is_empty());
+        assert!(ids(&index, r#""machine kubernetes""#).is_empty());
+    }
+
+    #[test]
+    fn prefixes_match_longer_words_below_exact_hits() {
+        let index = SearchIndex::build(&[
+            job("exact", "Java Developer", "Acme", ""),
+            job("longer", "Javascript Developer", "Initech", ""),
+            job("other", "Go Developer", "Globex", ""),
+        ]);
+        assert_eq!(ids(&index, "java"), vec!["exact", "longer"]);
+        let results = index.search("java*");
+        let scores: HashMap<String, f64> = results.hits.into_iter().collect();
+        assert_eq!(scores.len(), 2);
+        assert!((scores["exact"] - scores["longer"]).abs() < 1e-9, "{:?}", scores);
+        assert_eq!(results.matched_terms, terms(&["java", "javascript"]));
+        // Too short to expand
+        assert!(ids(&index, "j").is_empty());
+    }
+
+    #[test]
+    fn highlight_escapes_feed_text() {
+        let marked = highlight(r#"Rust & "Go" <script>alert('x')</script>"#, &terms(&["rust"]));
+        assert_eq!(marked, "<mark>Rust</mark> &amp; &quot;Go&quot; &lt;script&gt;alert(&#39;x&#39;)&lt;/script&gt;");
+        // Matched words are escaped inside the mark too
+        assert_eq!(highlight("AT&T <b>engineer</b>", &terms(&["att"])), "<mark>AT&amp;T</mark> &lt;b&gt;engineer&lt;/b&gt;");
+    }
+
+    #[test]
+    fn snippet_picks_the_window_with_most_matches() {
+        let mut words: Vec<String> = (0..100).map(|i| format!("w{}", i)).collect();
+        words[60] = "rust".into();
+        words[62] = "rust".into();
+        words[5] = "rust".into();
+        let text = words.join(" ");
+        let out = snippet(&text, &terms(&["rust"]));
+        assert!(out.starts_with("… w33 "), "{}", out);
+        assert!(out.ends_with(" w61 <mark>rust</mark> …"), "{}", out);
+        assert_eq!(out.matches("<mark>rust</mark>").count(), 2);
+        assert_eq!(out.split_whitespace().filter(|w| *w != "…").count(), SNIPPET_WORDS);
+
+        // Nothing matched: the opening words; short text: all of it
+        assert!(snippet(&text, &terms(&["go"])).starts_with("w0 w1 "));
+        assert_eq!(snippet("Rust & Go", &terms(&["go"])), "Rust &amp; <mark>Go</mark>");
+        assert_eq!(snippet("   ", &terms(&["go"])), "");
+    }
+}
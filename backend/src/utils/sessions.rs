@@ -0,0 +1,264 @@
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
+use actix_web::web;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::models::{RefreshTokenRecord, Role};
+use crate::state::AppState;
+use crate::utils::jwt::{hash_token, make_tokens};
+
+pub struct IssuedTokens {
+    pub access_token: String,
+    pub refresh_token: String,
+    pub expires_at: String,
+    pub refresh_expires_at: String,
+}
+
+pub enum RotateError {
+    // Never issued, or already swept after expiring
+    Unknown,
+    // Already exchanged or logged out: someone replayed it, so its session was revoked
+    Reused,
+    // The new token couldn't be persisted, so it wasn't handed out
+    Unsaved(std::io::Error),
+}
+
+fn is_expired(record: &RefreshTokenRecord, now: DateTime<Utc>) -> bool {
+    DateTime::parse_from_rfc3339(&record.expires_at).map(|t| t.with_timezone(&Utc) <= now).unwrap_or(true)
+}
+
+fn is_active(record: &RefreshTokenRecord, now: DateTime<Utc>) -> bool {
+    record.revoked_at.is_none() && !is_expired(record, now)
+}
+
+fn issue(state: &AppState, tokens: &mut HashMap<String, RefreshTokenRecord>, user_id: &str, role: Role, family_id: &str) -> IssuedTokens {
+    let (access_token, refresh_token, expires_at, refresh_expires_at) = make_tokens(user_id, role, family_id, &state.jwt_secret);
+    tokens.insert(hash_token(&refresh_token), RefreshTokenRecord {
+        user_id: user_id.to_string(),
+        family_id: family_id.to_string(),
+        issued_at: Utc::now().to_rfc3339(),
+        expires_at: refresh_expires_at.clone(),
+        revoked_at: None,
+        replaced_by: None,
+    });
+    IssuedTokens { access_token, refresh_token, expires_at, refresh_expires_at }
+}
+
+/// Revoke every live token matching `filter`; returns how many sessions that ended
+/// and the hashes of the tokens it revoked.
+fn revoke_where(tokens: &mut HashMap<String, RefreshTokenRecord>, filter: impl Fn(&RefreshTokenRecord) -> bool) -> (usize, Vec<String>) {
+    let now = Utc::now();
+    let mut families = HashSet::new();
+    let mut revoked = vec![];
+    for (hash, record) in tokens.iter_mut().filter(|(_, r)| filter(r) && is_active(r, now)) {
+        record.revoked_at = Some(now.to_rfc3339());
+        families.insert(record.family_id.clone());
+        revoked.push(hash.clone());
+    }
+    (families.len(), revoked)
+}
+
+/// Sign-in: a new session with its first access and refresh tokens.
+pub async fn start_session(state: &AppState, user_id: &str, role: Role) -> std::io::Result<IssuedTokens> {
+    let (issued, saved) = {
+        let mut tokens = state.refresh_tokens.lock().unwrap();
+        let issued = issue(state, &mut tokens, user_id, role, &Uuid::new_v4().to_string());
+        let saved = state.storage.save_refresh_tokens(&tokens, &[hash_token(&issued.refresh_token)]);
+        (issued, saved)
+    };
+    saved.await?;
+    Ok(issued)
+}
+
+/// Exchange a refresh token for a new pair in the same session. The old token is
+/// revoked; presenting it again revokes the whole session.
+pub async fn rotate(state: &AppState, refresh_token: &str, role: Role) -> Result<IssuedTokens, RotateError> {
+    let now = Utc::now();
+    let hash = hash_token(refresh_token);
+    let (issued, saved) = {
+        let mut tokens = state.refresh_tokens.lock().unwrap();
+        let record = match tokens.get(&hash) {
+            Some(r) if is_expired(r, now) => return Err(RotateError::Unknown),
+            Some(r) => r.clone(),
+            None => return Err(RotateError::Unknown),
+        };
+        if record.revoked_at.is_some() {
+            let (_, revoked) = revoke_where(&mut tokens, |r| r.family_id == record.family_id);
+            println!("🚨 Refresh token reuse for user {}, session {} revoked", record.user_id, record.family_id);
+            (None, state.storage.save_refresh_tokens(&tokens, &revoked))
+        } else {
+            let issued = issue(state, &mut tokens, &record.user_id, role, &record.family_id);
+            let new_hash = hash_token(&issued.refresh_token);
+            if let Some(old) = tokens.get_mut(&hash) {
+                old.revoked_at = Some(now.to_rfc3339());
+                old.replaced_by = Some(new_hash.clone());
+            }
+            let saved = state.storage.save_refresh_tokens(&tokens, &[hash, new_hash]);
+            (Some(issued), saved)
+        }
+    };
+    match issued {
+        Some(issued) => {
+            saved.await.map_err(RotateError::Unsaved)?;
+            Ok(issued)
+        }
+        None => {
+            // The revocation already applies in memory, so a failed save is only logged
+            if let Err(e) = saved.await { println!("⚠️ Failed to persist session revocation: {}", e); }
+            Err(RotateError::Reused)
+        }
+    }
+}
+
+/// End the session `refresh_token` belongs to. Returns the number of sessions ended (0 or 1).
+pub async fn end_session(state: &AppState, refresh_token: &str) -> std::io::Result<usize> {
+    let (ended, saved) = {
+        let mut tokens = state.refresh_tokens.lock().unwrap();
+        let family_id = match tokens.get(&hash_token(refresh_token)) {
+            Some(r) => r.family_id.clone(),
+            None => return Ok(0),
+        };
+        let (ended, revoked) = revoke_where(&mut tokens, |r| r.family_id == family_id);
+        (ended, state.storage.save_refresh_tokens(&tokens, &revoked))
+    };
+    saved.await?;
+    Ok(ended)
+}
+
+/// End every session of `user_id`, on all devices.
+pub async fn end_all_sessions(state: &AppState, user_id: &str) -> std::io::Result<usize> {
+    let (ended, saved) = {
+        let mut tokens = state.refresh_tokens.lock().unwrap();
+        let (ended, revoked) = revoke_where(&mut tokens, |r| r.user_id == user_id);
+        (ended, state.storage.save_refresh_tokens(&tokens, &revoked))
+    };
+    saved.await?;
+    Ok(ended)
+}
+
+/// Whether session `sid` still has a live refresh token, i.e. hasn't been logged out,
+/// revoked for reuse or left to expire.
+pub fn session_active(state: &AppState, sid: &str) -> bool {
+    let now = Utc::now();
+    state.refresh_tokens.lock().unwrap().values().any(|r| r.family_id == sid && is_active(r, now))
+}
+
+pub fn active_session_count(state: &AppState) -> usize {
+    let now = Utc::now();
+    state.refresh_tokens.lock().unwrap().values()
+        .filter(|r| is_active(r, now))
+        .map(|r| r.family_id.as_str())
+        .collect::<HashSet<_>>()
+        .len()
+}
+
+/// Drop tokens past their expiry. Revoked ones are kept until then so a replay is
+/// still recognised as reuse.
+pub async fn sweep_expired(state: &AppState) -> std::io::Result<usize> {
+    let now = Utc::now();
+    let (removed, saved) = {
+        let mut tokens = state.refresh_tokens.lock().unwrap();
+        let removed: Vec<String> = tokens.iter().filter(|(_, r)| is_expired(r, now)).map(|(h, _)| h.clone()).collect();
+        if removed.is_empty() { return Ok(0); }
+        for hash in removed.iter() { tokens.remove(hash); }
+        let saved = state.storage.save_refresh_tokens(&tokens, &removed);
+        (removed.len(), saved)
+    };
+    saved.await?;
+    Ok(removed)
+}
+
+/// Sweep expired refresh tokens every `REFRESH_TOKEN_SWEEP_SECS` (default hourly, 0 disables).
+pub async fn run_sweeper(state: web::Data<AppState>) {
+    let interval = std::env::var("REFRESH_TOKEN_SWEEP_SECS").ok().and_then(|v| v.parse().ok()).unwrap_or(3600);
+    if interval == 0 {
+        println!("ℹ️ Refresh token sweeper disabled (REFRESH_TOKEN_SWEEP_SECS=0)");
+        return;
+    }
+    loop {
+        match sweep_expired(&state).await {
+            Ok(0) => {}
+            Ok(removed) => println!("🧹 Removed {} expired refresh token(s)", removed),
+            Err(e) => println!("⚠️ Failed to persist swept refresh tokens: {}", e),
+        }
+        actix_web::rt::time::sleep(Duration::from_secs(interval)).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::{load_refresh_tokens, scratch_state};
+
+    fn family_of(state: &AppState, refresh_token: &str) -> String {
+        state.refresh_tokens.lock().unwrap()[&hash_token(refresh_token)].family_id.clone()
+    }
+
+    #[actix_web::test]
+    async fn rotation_replaces_the_token_within_the_session() {
+        let state = scratch_state();
+        let first = start_session(&state, "u1", Role::User).await.unwrap();
+        let sid = family_of(&state, &first.refresh_token);
+
+        let second = rotate(&state, &first.refresh_token, Role::User).await.ok().unwrap();
+        assert_ne!(second.refresh_token, first.refresh_token);
+        assert_eq!(family_of(&state, &second.refresh_token), sid);
+        {
+            let tokens = state.refresh_tokens.lock().unwrap();
+            let old = &tokens[&hash_token(&first.refresh_token)];
+            assert!(old.revoked_at.is_some());
+            assert_eq!(old.replaced_by, Some(hash_token(&second.refresh_token)));
+            assert!(tokens[&hash_token(&second.refresh_token)].revoked_at.is_none());
+        }
+        assert!(session_active(&state, &sid));
+        assert!(matches!(rotate(&state, "never-issued", Role::User).await, Err(RotateError::Unknown)));
+
+        let _ = std::fs::remove_dir_all(&state.data_dir);
+    }
+
+    #[actix_web::test]
+    async fn reusing_a_rotated_token_revokes_its_whole_session() {
+        let state = scratch_state();
+        let first = start_session(&state, "u1", Role::User).await.unwrap();
+        let other_device = start_session(&state, "u1", Role::User).await.unwrap();
+        let sid = family_of(&state, &first.refresh_token);
+        let second = rotate(&state, &first.refresh_token, Role::User).await.ok().unwrap();
+
+        assert!(matches!(rotate(&state, &first.refresh_token, Role::User).await, Err(RotateError::Reused)));
+        assert!(!session_active(&state, &sid));
+        // The token handed out by the rotation is revoked with the rest of its session
+        assert!(matches!(rotate(&state, &second.refresh_token, Role::User).await, Err(RotateError::Reused)));
+        // Other sessions of the user are untouched
+        assert!(session_active(&state, &family_of(&state, &other_device.refresh_token)));
+        assert_eq!(active_session_count(&state), 1);
+        // The revocation is persisted
+        assert!(load_refresh_tokens(&state.data_dir)[&hash_token(&second.refresh_token)].revoked_at.is_some());
+
+        let _ = std::fs::remove_dir_all(&state.data_dir);
+    }
+
+    #[actix_web::test]
+    async fn sweep_drops_only_expired_tokens() {
+        let state = scratch_state();
+        let live = start_session(&state, "u1", Role::User).await.unwrap();
+        let revoked = start_session(&state, "u1", Role::User).await.unwrap();
+        end_session(&state, &revoked.refresh_token).await.unwrap();
+        let expired = start_session(&state, "u2", Role::User).await.unwrap();
+        let past = (Utc::now() - chrono::Duration::minutes(1)).to_rfc3339();
+        state.refresh_tokens.lock().unwrap().get_mut(&hash_token(&expired.refresh_token)).unwrap().expires_at = past;
+
+        assert_eq!(sweep_expired(&state).await.unwrap(), 1);
+        assert_eq!(sweep_expired(&state).await.unwrap(), 0);
+        let saved = load_refresh_tokens(&state.data_dir);
+        assert!(!saved.contains_key(&hash_token(&expired.refresh_token)));
+        assert!(saved.contains_key(&hash_token(&live.refresh_token)));
+        // Revoked but unexpired tokens stay, so a replay is still caught as reuse
+        assert!(saved.contains_key(&hash_token(&revoked.refresh_token)));
+        assert!(matches!(rotate(&state, &revoked.refresh_token, Role::User).await, Err(RotateError::Reused)));
+        assert!(matches!(rotate(&state, &expired.refresh_token, Role::User).await, Err(RotateError::Unknown)));
+
+        let _ = std::fs::remove_dir_all(&state.data_dir);
+    }
+}
@@ -0,0 +1,86 @@
+use std::collections::HashMap;
+use chrono::DateTime;
+
+use crate::models::{Application, ApplicationStatistics, ApplicationStatus, ResponseRate};
+
+/// First status change made by the employer side, i.e. anything other than
+/// pending or the applicant withdrawing.
+fn first_response_at(app: &Application) -> Option<&str> {
+    app.status_history.iter()
+        .find(|h| !matches!(h.status, ApplicationStatus::Pending | ApplicationStatus::Withdrawn))
+        .map(|h| h.changed_at.as_str())
+}
+
+fn hours_between(from: &str, to: &str) -> Option<f64> {
+    let from = DateTime::parse_from_rfc3339(from).ok()?;
+    let to = DateTime::parse_from_rfc3339(to).ok()?;
+    Some((to - from).num_seconds().max(0) as f64 / 3600.0)
+}
+
+fn median(values: &mut [f64]) -> Option<f64> {
+    if values.is_empty() { return None; }
+    values.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    let mid = values.len() / 2;
+    let m = if values.len().is_multiple_of(2) { (values[mid - 1] + values[mid]) / 2.0 } else { values[mid] };
+    Some((m * 10.0).round() / 10.0)
+}
+
+fn response_rates(groups: HashMap<String, (usize, usize)>) -> Vec<ResponseRate> {
+    let mut rates: Vec<ResponseRate> = groups.into_iter().map(|(name, (applications, responses))| ResponseRate {
+        name,
+        applications,
+        responses,
+        response_rate: responses as f64 / applications as f64,
+    }).collect();
+    rates.sort_by(|a, b| b.applications.cmp(&a.applications).then_with(|| a.name.cmp(&b.name)));
+    rates
+}
+
+pub fn compute_application_stats(apps: &[Application]) -> ApplicationStatistics {
+    let mut by_status: HashMap<String, usize> = ApplicationStatus::ALL.iter().map(|s| (s.as_str().to_string(), 0)).collect();
+    let mut by_category: HashMap<String, (usize, usize)> = HashMap::new();
+    let mut by_company: HashMap<String, (usize, usize)> = HashMap::new();
+    let mut response_hours: Vec<f64> = vec![];
+    let mut interviewed = 0;
+    let mut responded_total = 0;
+    let mut eligible_total = 0;
+
+    for app in apps.iter() {
+        *by_status.entry(app.status.as_str().to_string()).or_insert(0) += 1;
+        if app.status_history.iter().any(|h| h.status == ApplicationStatus::Interviewing) || app.status == ApplicationStatus::Interviewing {
+            interviewed += 1;
+        }
+
+        let response = first_response_at(app);
+        // Withdrawn before anyone answered says nothing about the employer
+        if response.is_none() && app.status == ApplicationStatus::Withdrawn { continue; }
+        let responded = response.is_some() as usize;
+        eligible_total += 1;
+        responded_total += responded;
+        if let Some(at) = response {
+            if let Some(h) = hours_between(&app.applied_at, at) { response_hours.push(h); }
+        }
+
+        let category = app.job_category.clone().unwrap_or_else(|| "Uncategorized".to_string());
+        let entry = by_category.entry(category).or_insert((0, 0));
+        entry.0 += 1;
+        entry.1 += responded;
+        let company = app.company_name.clone().unwrap_or_else(|| "Unknown".to_string());
+        let entry = by_company.entry(company).or_insert((0, 0));
+        entry.0 += 1;
+        entry.1 += responded;
+    }
+
+    ApplicationStatistics {
+        total: apps.len(),
+        pending: by_status["pending"],
+        interviewed,
+        rejected: by_status["rejected"],
+        accepted: by_status["accepted"],
+        by_status,
+        response_rate: if eligible_total > 0 { responded_total as f64 / eligible_total as f64 } else { 0.0 },
+        by_category: response_rates(by_category),
+        by_company: response_rates(by_company),
+        median_response_hours: median(&mut response_hours),
+    }
+}
@@ -1,3 +1,7 @@
+use std::sync::LazyLock;
+
+use regex::Regex;
+
 pub fn normalize_token(s: &str) -> String {
     s.to_lowercase()
         .chars()
@@ -15,3 +19,10 @@ pub fn tokenize(text: &str) -> Vec<String> {
         .collect()
 }
 
+
+static HTML_TAG: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"<[^>]+>").unwrap());
+
+/// Strip HTML tags from a string to plain text
+pub fn strip_html(input: &str) -> String {
+    HTML_TAG.replace_all(input, " ").to_string()
+}
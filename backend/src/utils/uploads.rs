@@ -0,0 +1,154 @@
+use actix_multipart::{Field, Multipart};
+use actix_web::error::InternalError;
+use actix_web::{http::header, HttpResponse};
+use chrono::Utc;
+use futures_util::TryStreamExt as _;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+use crate::models::{ApiResponse, ResumeRecord};
+
+// Resume formats we accept, by extension. The type is never taken from the client, so
+// nothing uploaded can be served back as HTML or SVG.
+const RESUME_TYPES: [(&str, &str); 4] = [
+    ("pdf", "application/pdf"),
+    ("doc", "application/msword"),
+    ("docx", "application/vnd.openxmlformats-officedocument.wordprocessingml.document"),
+    ("txt", "text/plain"),
+];
+
+/// The content type of an accepted resume file, from its extension.
+pub fn resume_content_type(filename: &str) -> Option<&'static str> {
+    let ext = Path::new(filename).extension()?.to_str()?.to_lowercase();
+    RESUME_TYPES.iter().find(|(e, _)| *e == ext).map(|(_, t)| *t)
+}
+
+pub struct SavedUpload {
+    pub path: PathBuf,
+    pub filename: String,
+    pub original_filename: String,
+    pub content_type: String,
+    pub size_bytes: u64,
+    // `{timestamp}_{sanitized name}`, the part of `filename` after the prefix
+    stem: String,
+}
+
+impl SavedUpload {
+    /// The storage record of this upload as a resume, for a profile or an application version.
+    pub fn resume_record(&self, user_id: &str, application_id: Option<&str>, version: Option<u32>) -> ResumeRecord {
+        ResumeRecord {
+            id: Uuid::new_v4().to_string(),
+            user_id: user_id.to_string(),
+            application_id: application_id.map(|a| a.to_string()),
+            version,
+            filename: self.filename.clone(),
+            original_filename: self.original_filename.clone(),
+            content_type: self.content_type.clone(),
+            size_bytes: self.size_bytes,
+            uploaded_at: Utc::now().to_rfc3339(),
+        }
+    }
+
+    /// Rename the stored file to `{prefix}_{timestamp}_{name}`, in the same directory.
+    pub fn set_prefix(&mut self, prefix: &str) -> std::io::Result<()> {
+        let filename = format!("{}_{}", prefix, self.stem);
+        let path = self.path.with_file_name(&filename);
+        fs::rename(&self.path, &path)?;
+        self.path = path;
+        self.filename = filename;
+        Ok(())
+    }
+}
+
+/// A stored resume as a download. The type comes from the stored file's extension,
+/// never from what was recorded at upload, and browsers are told not to sniff or render it.
+pub fn serve_resume_file(stored_name: &str, download_name: &str, data: Vec<u8>) -> HttpResponse {
+    HttpResponse::Ok()
+        .insert_header((header::CONTENT_TYPE, resume_content_type(stored_name).unwrap_or("application/octet-stream")))
+        .insert_header((header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}\"", sanitize_filename(download_name))))
+        .insert_header((header::X_CONTENT_TYPE_OPTIONS, "nosniff"))
+        .body(data)
+}
+
+fn rejected(message: &str) -> actix_web::Error {
+    let response = HttpResponse::BadRequest().json(ApiResponse::<serde_json::Value> { success: false, data: None, message: message.to_string() });
+    InternalError::from_response(message.to_string(), response).into()
+}
+
+/// Sanitize filename to prevent path traversal and other issues
+pub fn sanitize_filename(filename: &str) -> String {
+    let filename = Path::new(filename)
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or("unnamed.pdf");
+
+    // Replace potentially dangerous characters
+    let re = regex::Regex::new(r"[^a-zA-Z0-9_.%-]").unwrap();
+    let sanitized = re.replace_all(filename, "_").to_string();
+
+    // Ensure filename isn't too long
+    if sanitized.len() > 100 {
+        sanitized[..100].to_string()
+    } else {
+        sanitized
+    }
+}
+
+async fn write_field(field: &mut Field, path: &Path) -> Result<u64, actix_web::Error> {
+    let mut f = fs::File::create(path).map_err(actix_web::error::ErrorInternalServerError)?;
+    let mut size_bytes = 0u64;
+    while let Some(chunk) = field.try_next().await? {
+        f.write_all(&chunk).map_err(actix_web::error::ErrorInternalServerError)?;
+        size_bytes += chunk.len() as u64;
+    }
+    Ok(size_bytes)
+}
+
+/// Write the single file field of a multipart upload into `dir` as `{prefix}_{timestamp}_{name}`.
+/// Files that aren't PDF, DOC, DOCX or TXT and uploads with more than one file are
+/// rejected with a 400, and nothing of them is kept.
+pub async fn save_multipart_file(payload: &mut Multipart, dir: &Path, prefix: &str) -> Result<Option<SavedUpload>, actix_web::Error> {
+    if !dir.exists() { fs::create_dir_all(dir).map_err(actix_web::error::ErrorInternalServerError)?; }
+
+    let mut saved: Option<SavedUpload> = None;
+    while let Some(mut field) = payload.try_next().await? {
+        if let Some(first) = saved.take() {
+            let _ = fs::remove_file(&first.path);
+            return Err(rejected("Upload one file at a time"));
+        }
+        let original_filename = field.content_disposition().get_filename().map(|f| f.to_string()).unwrap_or_else(|| "resume.pdf".into());
+        let content_type = resume_content_type(&original_filename)
+            .ok_or_else(|| rejected("Unsupported file type; upload a PDF, DOC, DOCX or TXT file"))?
+            .to_string();
+        let stem = format!("{}_{}", Utc::now().timestamp(), sanitize_filename(&original_filename));
+        let target_name = format!("{}_{}", prefix, stem);
+        let save_path = dir.join(&target_name);
+
+        let size_bytes = match write_field(&mut field, &save_path).await {
+            Ok(n) => n,
+            Err(e) => {
+                let _ = fs::remove_file(&save_path);
+                return Err(e);
+            }
+        };
+        saved = Some(SavedUpload { path: save_path, filename: target_name, original_filename, content_type, size_bytes, stem });
+    }
+    Ok(saved)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_resume_formats_are_accepted() {
+        assert_eq!(resume_content_type("CV.PDF"), Some("application/pdf"));
+        assert_eq!(resume_content_type("cv.docx"), Some("application/vnd.openxmlformats-officedocument.wordprocessingml.document"));
+        assert_eq!(resume_content_type("notes.txt"), Some("text/plain"));
+        for name in ["cv.html", "cv.svg", "cv.pdf.html", "cv", ".pdf", "cv.exe"] {
+            assert_eq!(resume_content_type(name), None, "{}", name);
+        }
+    }
+}
@@ -4,11 +4,53 @@ import Input from '../components/ui/Input';
 import Button from '../components/ui/Button';
 import { MotiView } from 'moti';
 import { useNavigation } from '@react-navigation/native';
+import apiService from '../services/api';
+
+type Step = 'request' | 'reset' | 'done';
 
 export default function ForgotPasswordScreen() {
   const [email, setEmail] = useState('');
+  const [code, setCode] = useState('');
+  const [password, setPassword] = useState('');
+  const [step, setStep] = useState<Step>('request');
+  const [loading, setLoading] = useState(false);
+  const [error, setError] = useState<string | null>(null);
   const navigation = useNavigation();
 
+  const sendInstructions = async () => {
+    if (!email.trim()) {
+      setError('Please enter your email');
+      return;
+    }
+    setLoading(true);
+    setError(null);
+    try {
+      await apiService.forgotPassword(email.trim());
+      setStep('reset');
+    } catch (e: any) {
+      setError(e?.response?.data?.message || e?.message || 'Could not send instructions');
+    } finally {
+      setLoading(false);
+    }
+  };
+
+  const resetPassword = async () => {
+    if (!code.trim() || !password) {
+      setError('Please enter the code from the email and a new password');
+      return;
+    }
+    setLoading(true);
+    setError(null);
+    try {
+      await apiService.resetPassword(email.trim(), code.trim(), password);
+      setStep('done');
+    } catch (e: any) {
+      setError(e?.response?.data?.message || e?.message || 'Could not reset password');
+    } finally {
+      setLoading(false);
+    }
+  };
+
   return (
     <View className="flex-1 bg-gradient-to-br from-blue-100 via-white to-blue-200 justify-center items-center px-6">
       <MotiView
@@ -21,22 +63,64 @@ export default function ForgotPasswordScreen() {
           <Text className="text-2xl font-bold text-blue-700 mb-6 text-center tracking-tight">
             Forgot Password
           </Text>
-          <Text className="text-base text-gray-600 mb-6 text-center">
-            Enter your email and we'll send you instructions to reset your password.
-          </Text>
-          <Input
-            value={email}
-            onChangeText={setEmail}
-            placeholder="Email"
-            leftIcon="mail"
-            keyboardType="email-address"
-            autoCapitalize="none"
-          />
-          <Button
-            title="Send Instructions"
-            style={{ width: '100%', backgroundColor: '#2563eb', borderRadius: 16, paddingVertical: 12, marginBottom: 8 }}
-            onPress={() => {/* TODO: Handle Forgot Password */}}
-          />
+          {step === 'request' && (
+            <>
+              <Text className="text-base text-gray-600 mb-6 text-center">
+                Enter your email and we'll send you instructions to reset your password.
+              </Text>
+              <Input
+                value={email}
+                onChangeText={setEmail}
+                placeholder="Email"
+                leftIcon="mail"
+                keyboardType="email-address"
+                autoCapitalize="none"
+              />
+              <Button
+                title="Send Instructions"
+                style={{ width: '100%', backgroundColor: '#2563eb', borderRadius: 16, paddingVertical: 12, marginBottom: 8 }}
+                onPress={sendInstructions}
+                loading={loading}
+              />
+            </>
+          )}
+          {step === 'reset' && (
+            <>
+              <Text className="text-base text-gray-600 mb-6 text-center">
+                If an account exists for {email.trim()}, we've emailed it a reset code. Enter it with your new password.
+              </Text>
+              <Input
+                value={code}
+                onChangeText={setCode}
+                placeholder="Reset code (e.g. ABCD-EFGH)"
+                leftIcon="key"
+                autoCapitalize="characters"
+              />
+              <Input
+                value={password}
+                onChangeText={setPassword}
+                placeholder="New password (at least 8 characters)"
+                leftIcon="lock-closed"
+                secureTextEntry
+              />
+              <Button
+                title="Reset Password"
+                style={{ width: '100%', backgroundColor: '#2563eb', borderRadius: 16, paddingVertical: 12, marginBottom: 8 }}
+                onPress={resetPassword}
+                loading={loading}
+              />
+            </>
+          )}
+          {step === 'done' && (
+            <Text className="text-base text-gray-600 mb-6 text-center">
+              Your password has been reset and you've been signed out on all devices. Sign in with your new password.
+            </Text>
+          )}
+          {error && (
+            <Text className="text-red-600 text-sm text-center font-medium mt-2">
+              {error}
+            </Text>
+          )}
           <TouchableOpacity
             style={{ marginTop: 16 }}
             onPress={() => navigation.goBack()}
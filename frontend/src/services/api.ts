@@ -196,6 +196,26 @@ class ApiService {
     return response.data;
   }
 
+  async forgotPassword(email: string): Promise<ApiResponse<any>> {
+    const response: AxiosResponse<ApiResponse<any>> = await this.api.post('/auth/password/forgot', { email });
+    return response.data;
+  }
+
+  async resetPassword(email: string, token: string, newPassword: string): Promise<ApiResponse<any>> {
+    const response: AxiosResponse<ApiResponse<any>> = await this.api.post('/auth/password/reset', { email, token, new_password: newPassword });
+    return response.data;
+  }
+
+  async logout(refreshToken: string): Promise<ApiResponse<any>> {
+    const response: AxiosResponse<ApiResponse<any>> = await this.api.post('/auth/logout', { refresh_token: refreshToken });
+    return response.data;
+  }
+
+  async logoutAll(): Promise<ApiResponse<any>> {
+    const response: AxiosResponse<ApiResponse<any>> = await this.api.post('/auth/logout-all');
+    return response.data;
+  }
+
   async loginWithGoogle(idToken: string): Promise<ApiResponse<AuthResponse>> {
     const response: AxiosResponse<ApiResponse<AuthResponse>> = await this.api.post('/auth/google', { id_token: idToken });
     return response.data;
@@ -71,7 +71,9 @@ export const signInWithGoogle = createAsyncThunk<
       console.log('[authSlice] signInWithGoogle web flow');
       const provider = new GoogleAuthProvider();
       const result = await signInWithPopup(auth, provider);
-      const idToken = await result.user.getIdToken();
+      // The backend verifies Google's own ID token, not the Firebase session token
+      const idToken = GoogleAuthProvider.credentialFromResult(result)?.idToken;
+      if (!idToken) throw new Error('Google sign-in did not return an id_token');
 
       // Use backend API for Google authentication
       const response = await apiService.loginWithGoogle(idToken);
@@ -267,6 +269,16 @@ export const logoutUser = createAsyncThunk<
     async (_, { rejectWithValue }) => {
       console.log('[authSlice] logoutUser invoked');
       try {
+        // End the session on the server so the tokens can't be used again
+        try {
+          const refreshToken = await secureStorage.getItem('refresh_token');
+          if (refreshToken) {
+            await apiService.logout(refreshToken);
+          }
+        } catch (e) {
+          console.log('[authSlice] Server logout failed (ignored):', e);
+        }
+
         // Clear secure storage including refresh token
         await secureStorage.removeItem('auth_token');
         await secureStorage.removeItem('refresh_token');
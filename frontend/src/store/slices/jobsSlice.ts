@@ -14,20 +14,8 @@ export const fetchJobs = createAsyncThunk<
       const { append = false, ...params } = searchParams;
       const resp = await apiService.getJobs(params);
       if (resp.success && resp.data) {
-        let list = resp.data.jobs || [];
-        // If empty and not appending, try to sync from Remotive then fetch again
-        if (!list || (list.length === 0 && !append)) {
-          const sync = await apiService.syncJobs();
-          if (!sync.success) {
-            return rejectWithValue(sync.message || 'Sync failed');
-          }
-          const resp2 = await apiService.getJobs(params);
-          if (resp2.success && resp2.data && resp2.data.jobs) {
-            return { jobs: resp2.data.jobs, append };
-          }
-          return { jobs: [], append };
-        }
-        return { jobs: list, append };
+        // The catalog is filled by the server's background sync; triggering one is admin-only
+        return { jobs: resp.data.jobs || [], append };
       } else {
         return rejectWithValue(resp.message || 'Failed to fetch jobs');
       }
@@ -17,6 +17,7 @@ export interface User {
   created_at: string;
   updated_at: string;
   resume_url?: string; // Add resume_url field
+  role?: 'user' | 'admin';
 }
 
 export interface AuthResponse {